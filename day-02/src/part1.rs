@@ -1,39 +1,19 @@
-use nom::{
-    IResult, Parser,
-    bytes::complete::tag,
-    character::complete,
-    multi::separated_list1,
-    sequence::separated_pair,
+use crate::{
+    ranges::{checked_sum, parse_ranges},
+    repeats::RepeatQuery,
 };
 
 #[tracing::instrument]
 pub fn process(input: &str) -> miette::Result<String> {
-    let (_, ranges) = ranges(input).map_err(|e| miette::miette!("Parse error: {:?}", e))?;
-    let mut counter = 0;
+    let ranges = parse_ranges::<u128>(input)?;
+    let query = RepeatQuery::exactly(2);
 
-    for range in ranges {
-        let (start, end) = range;
-        // println!("processing range {} to {}", start, end);
-        for num in start..=end {
-            let s = num.to_string();
-            if s.len() % 2 == 1 {
-                continue;
-            }
-            let mid = s.len() / 2;
-            let s1: i64 = s[..mid].parse().unwrap();
-            let s2: i64 = s[mid..].parse().unwrap();
-            if s1 == s2 {
-                // println!("adding {}", num);
-                counter += num;
-            }
-        }
-    }
-
-    Ok(counter.to_string())
-}
+    let sums = ranges
+        .iter()
+        .map(|range| query.sum(range))
+        .collect::<miette::Result<Vec<_>>>()?;
 
-fn ranges(input: &str) -> IResult<&str, Vec<(i64, i64)>> {
-    separated_list1(tag(","), separated_pair(complete::i64, tag("-"), complete::i64)).parse(input)
+    Ok(checked_sum(sums)?.to_string())
 }
 
 #[cfg(test)]
@@ -46,4 +26,22 @@ mod tests {
         assert_eq!("1227775554", process(input)?);
         Ok(())
     }
+
+    #[test]
+    fn test_overflow() -> miette::Result<()> {
+        let nines = "9".repeat(38);
+        let range = format!("{nines}-{nines}");
+        assert_eq!(format!("2{}7", "9".repeat(37)), process(&[range.as_str(); 3].join(","))?);
+        assert!(process(&[range.as_str(); 4].join(",")).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_repeated_ranges() -> miette::Result<()> {
+        // an ID in two ranges is counted for both
+        assert_eq!("33", process("11-22")?);
+        assert_eq!("66", process("11-22,11-22")?);
+        assert_eq!("88", process("11-22,11-22,22-22")?);
+        Ok(())
+    }
 }
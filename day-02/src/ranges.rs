@@ -0,0 +1,143 @@
+use std::{fmt::Display, ops::RangeInclusive, str::FromStr};
+
+use interval_set::{Endpoint, IntervalSet};
+use miette::miette;
+use nom::{
+    IResult, Parser,
+    bytes::complete::tag,
+    character::complete::digit1,
+    multi::separated_list1,
+    sequence::separated_pair,
+};
+
+/// Unsigned integer widths an ID range can be parsed into.
+pub trait Id: Endpoint + Display + FromStr + Send + Sync {
+    const ZERO: Self;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+}
+
+macro_rules! impl_id {
+    ($($t:ty),*) => {
+        $(
+            impl Id for $t {
+                const ZERO: Self = 0;
+
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$t>::checked_add(self, other)
+                }
+            }
+        )*
+    };
+}
+
+impl_id!(u32, u64, u128);
+
+/// The ranges as they were given, so an ID in two of them counts twice, and
+/// the IDs they cover between them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdRanges<T> {
+    ranges: Vec<RangeInclusive<T>>,
+    covered: IntervalSet<T>,
+}
+
+impl<T: Id> IdRanges<T> {
+    pub fn ranges(&self) -> &[RangeInclusive<T>] {
+        &self.ranges
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.ranges.iter().cloned()
+    }
+
+    pub fn covered(&self) -> &IntervalSet<T> {
+        &self.covered
+    }
+
+    pub fn contains(&self, id: T) -> bool {
+        self.covered.contains(id)
+    }
+
+    /// True when some ID is in more than one range.
+    pub fn overlaps(&self) -> bool {
+        let total = self
+            .ranges
+            .iter()
+            .fold(0u128, |acc, r| acc.saturating_add(T::span(*r.start(), *r.end())));
+        total > self.covered.len()
+    }
+}
+
+/// Parse the comma separated `start-end` list into ranges of any [`Id`] width.
+/// IDs that don't fit in `T` are reported instead of being truncated, and so
+/// are ranges that end before they start.
+pub fn parse_ranges<T: Id>(input: &str) -> miette::Result<IdRanges<T>> {
+    let (_, ranges) = digit_ranges(input.trim()).map_err(|e| miette!("Parse error: {:?}", e))?;
+
+    let ranges = ranges
+        .into_iter()
+        .map(|(start, end)| {
+            let range = parse_id::<T>(start)?..=parse_id::<T>(end)?;
+            if range.is_empty() {
+                return Err(miette!("range {}-{} ends before it starts", start, end));
+            }
+            Ok(range)
+        })
+        .collect::<miette::Result<Vec<_>>>()?;
+    let covered = ranges.iter().cloned().collect();
+    Ok(IdRanges { ranges, covered })
+}
+
+/// The raw decimal digits of every range, for IDs of any length.
+pub fn digit_ranges(input: &str) -> IResult<&str, Vec<(&str, &str)>> {
+    separated_list1(tag(","), separated_pair(digit1, tag("-"), digit1)).parse(input)
+}
+
+fn parse_id<T: Id>(digits: &str) -> miette::Result<T> {
+    digits
+        .parse()
+        .map_err(|_| miette!("ID {} does not fit in {}", digits, std::any::type_name::<T>()))
+}
+
+/// Sum the IDs, failing instead of wrapping when the total no longer fits in `T`.
+pub fn checked_sum<T: Id>(ids: impl IntoIterator<Item = T>) -> miette::Result<T> {
+    ids.into_iter().try_fold(T::ZERO, |acc, id| {
+        acc.checked_add(id)
+            .ok_or_else(|| miette!("sum overflows {} when adding ID {}", std::any::type_name::<T>(), id))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ranges() -> miette::Result<()> {
+        assert_eq!(&[11u128..=22, 95..=115], parse_ranges::<u128>("11-22,95-115\n")?.ranges());
+        assert!(parse_ranges::<u64>("1-99999999999999999999").is_err());
+        assert_eq!(
+            &[1u128..=99999999999999999999],
+            parse_ranges::<u128>("1-99999999999999999999")?.ranges()
+        );
+        assert!(parse_ranges::<u64>("22-11").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_overlapping_ranges() -> miette::Result<()> {
+        // repeats and overlaps are kept, only the coverage is merged
+        let ranges = parse_ranges::<u64>("15-30,11-22,15-30")?;
+        assert_eq!(&[15..=30, 11..=22, 15..=30], ranges.ranges());
+        assert_eq!(&[11..=30], ranges.covered().ranges());
+        assert!(ranges.overlaps());
+        assert!(ranges.contains(11) && !ranges.contains(31));
+        assert!(!parse_ranges::<u64>("11-22,23-30")?.overlaps());
+        Ok(())
+    }
+
+    #[test]
+    fn test_checked_sum() {
+        assert_eq!(6u32, checked_sum([1u32, 2, 3]).unwrap());
+        assert!(checked_sum([u64::MAX, 1]).is_err());
+    }
+}
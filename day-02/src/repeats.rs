@@ -0,0 +1,304 @@
+use std::ops::RangeInclusive;
+
+use miette::miette;
+
+/// How many times a block has to be repeated for an ID to match.
+/// An ID of `n` digits is `k` repeats when `k` divides `n` and all `k`
+/// blocks of `n / k` digits are the same, so `1111` is both 2 and 4 repeats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Repeats {
+    Exactly(u32),
+    AtLeast(u32),
+}
+
+/// Finds the IDs in a range that are made of a repeated block of digits
+/// when written in `base`. The fields are private so the base always goes
+/// through [`RepeatQuery::in_base`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RepeatQuery {
+    base: u32,
+    repeats: Repeats,
+}
+
+impl RepeatQuery {
+    pub fn exactly(k: u32) -> Self {
+        Self { base: 10, repeats: Repeats::Exactly(k) }
+    }
+
+    pub fn at_least(k: u32) -> Self {
+        Self { base: 10, repeats: Repeats::AtLeast(k) }
+    }
+
+    pub fn in_base(self, base: u32) -> miette::Result<Self> {
+        if base < 2 {
+            return Err(miette!("base must be at least 2, got {}", base));
+        }
+        Ok(Self { base, ..self })
+    }
+
+    pub fn base(&self) -> u32 {
+        self.base
+    }
+
+    pub fn repeats(&self) -> Repeats {
+        self.repeats
+    }
+
+    /// Does this single ID match the query.
+    pub fn matches(&self, id: u128) -> bool {
+        let len = digit_len(id, self.base);
+        self.blocks(len)
+            .iter()
+            .any(|&(m, k)| multiplier(self.base, m, k).is_some_and(|r| id.is_multiple_of(r)))
+    }
+
+    /// The matching IDs in ascending order, computed lazily.
+    pub fn iter(&self, range: RangeInclusive<u128>) -> RepeatIter {
+        RepeatIter {
+            query: *self,
+            range: range.clone(),
+            len: digit_len(*range.start(), self.base),
+            streams: Vec::new(),
+            done: range.is_empty(),
+        }
+    }
+
+    pub fn count(&self, range: RangeInclusive<u128>) -> u128 {
+        self.fold(range, |a| Some(a.count()))
+            .expect("a count never exceeds the size of the range")
+    }
+
+    pub fn sum(&self, range: RangeInclusive<u128>) -> miette::Result<u128> {
+        self.fold(range.clone(), |a| a.sum())
+            .ok_or_else(|| miette!("sum of repeated IDs in {}-{} overflows u128", range.start(), range.end()))
+    }
+
+    // inclusion-exclusion over the repeat counts that can produce an ID of
+    // each length, every term is an arithmetic progression of blocks
+    fn fold(&self, range: RangeInclusive<u128>, value: impl Fn(&Progression) -> Option<u128>) -> Option<u128> {
+        let (lo, hi) = (*range.start(), *range.end());
+        if lo > hi {
+            return Some(0);
+        }
+
+        let mut added = 0u128;
+        let mut removed = 0u128;
+
+        for len in digit_len(lo, self.base)..=digit_len(hi, self.base) {
+            let counts: Vec<u32> = self.blocks(len).iter().map(|&(_, k)| k).collect();
+
+            for subset in 1..(1u32 << counts.len()) {
+                let k = (0..counts.len())
+                    .filter(|i| subset & (1 << i) != 0)
+                    .fold(1, |acc, i| lcm(acc, counts[i]));
+                if !len.is_multiple_of(k) {
+                    continue;
+                }
+                let Some(progression) = Progression::new(self.base, len, k, lo, hi) else {
+                    continue;
+                };
+                let v = value(&progression)?;
+                if subset.count_ones() % 2 == 1 {
+                    added = added.checked_add(v)?;
+                } else {
+                    removed = removed.checked_add(v)?;
+                }
+            }
+        }
+
+        Some(added - removed)
+    }
+
+    // the (block length, repeat count) pairs that make up an ID of `len`
+    // digits, leaving out counts that are implied by a smaller one
+    fn blocks(&self, len: u32) -> Vec<(u32, u32)> {
+        let counts: Vec<u32> = match self.repeats {
+            Repeats::Exactly(k) => vec![k],
+            Repeats::AtLeast(k) => (k.max(1)..=len).collect(),
+        };
+
+        counts
+            .iter()
+            .copied()
+            .filter(|&k| k > 0 && len.is_multiple_of(k))
+            .filter(|&k| !counts.iter().any(|&j| j < k && j > 0 && len.is_multiple_of(j) && k.is_multiple_of(j)))
+            .map(|k| (len / k, k))
+            .collect()
+    }
+}
+
+/// Iterator over the IDs matching a [`RepeatQuery`], see [`RepeatQuery::iter`].
+#[derive(Debug, Clone)]
+pub struct RepeatIter {
+    query: RepeatQuery,
+    range: RangeInclusive<u128>,
+    len: u32,
+    streams: Vec<Progression>,
+    done: bool,
+}
+
+impl Iterator for RepeatIter {
+    type Item = u128;
+
+    fn next(&mut self) -> Option<u128> {
+        while !self.done {
+            let next = self.streams.iter().filter_map(|s| s.first()).min();
+
+            if let Some(next) = next {
+                // several block sizes can produce the same ID, advance all of them
+                for stream in &mut self.streams {
+                    if stream.first() == Some(next) {
+                        stream.start += 1;
+                    }
+                }
+                return Some(next);
+            }
+
+            self.next_len();
+        }
+        None
+    }
+}
+
+impl RepeatIter {
+    fn next_len(&mut self) {
+        let (lo, hi) = (*self.range.start(), *self.range.end());
+        if self.len > digit_len(hi, self.query.base) {
+            self.done = true;
+            return;
+        }
+
+        self.streams = self
+            .query
+            .blocks(self.len)
+            .iter()
+            .filter_map(|&(_, k)| Progression::new(self.query.base, self.len, k, lo, hi))
+            .collect();
+        self.len += 1;
+    }
+}
+
+// the IDs `block * multiplier` for every block in `start..=end`
+#[derive(Debug, Clone)]
+struct Progression {
+    multiplier: u128,
+    start: u128,
+    end: u128,
+}
+
+impl Progression {
+    fn new(base: u32, len: u32, k: u32, lo: u128, hi: u128) -> Option<Self> {
+        let m = len / k;
+        let multiplier = multiplier(base, m, k)?;
+        let smallest = (base as u128).checked_pow(m - 1)?;
+        let largest = (base as u128).checked_pow(m).map_or(u128::MAX, |p| p - 1);
+
+        let start = lo.div_ceil(multiplier).max(smallest);
+        let end = (hi / multiplier).min(largest);
+        (start <= end).then_some(Self { multiplier, start, end })
+    }
+
+    fn first(&self) -> Option<u128> {
+        (self.start <= self.end).then(|| self.start * self.multiplier)
+    }
+
+    fn count(&self) -> u128 {
+        self.end - self.start + 1
+    }
+
+    fn sum(&self) -> Option<u128> {
+        let n = self.count();
+        let (a, b) = (self.start, self.end);
+        let blocks = if n.is_multiple_of(2) {
+            (n / 2).checked_mul(a.checked_add(b)?)?
+        } else {
+            n.checked_mul(a + (b - a) / 2)?
+        };
+        blocks.checked_mul(self.multiplier)
+    }
+}
+
+// 1 + base^m + base^2m + ... repeated k times, so that block * multiplier
+// writes the block out k times
+fn multiplier(base: u32, m: u32, k: u32) -> Option<u128> {
+    let step = (base as u128).checked_pow(m)?;
+    (0..k).try_fold(0u128, |acc, _| acc.checked_mul(step)?.checked_add(1))
+}
+
+fn digit_len(mut n: u128, base: u32) -> u32 {
+    let mut len = 1;
+    while n >= base as u128 {
+        n /= base as u128;
+        len += 1;
+    }
+    len
+}
+
+fn lcm(a: u32, b: u32) -> u32 {
+    let gcd = |mut a: u32, mut b: u32| {
+        while b != 0 {
+            (a, b) = (b, a % b);
+        }
+        a
+    };
+    a / gcd(a, b) * b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    // write the ID out and compare its blocks
+    fn is_repeated(id: u128, base: u32, repeats: Repeats) -> bool {
+        let digits = match base {
+            2 => format!("{id:b}"),
+            16 => format!("{id:x}"),
+            _ => id.to_string(),
+        };
+        let len = digits.len();
+        let (min, max) = match repeats {
+            Repeats::Exactly(k) => (k as usize, k as usize),
+            Repeats::AtLeast(k) => (k as usize, len),
+        };
+        (min..=max.min(len)).filter(|k| len % k == 0).any(|k| {
+            let block = &digits[..len / k];
+            digits == block.repeat(k)
+        })
+    }
+
+    #[rstest]
+    #[case(RepeatQuery::exactly(2))]
+    #[case(RepeatQuery::exactly(3))]
+    #[case(RepeatQuery::at_least(2))]
+    #[case(RepeatQuery::at_least(3))]
+    #[case(RepeatQuery::at_least(2).in_base(2).unwrap())]
+    #[case(RepeatQuery::exactly(2).in_base(16).unwrap())]
+    fn test_matches_brute_force(#[case] query: RepeatQuery) -> miette::Result<()> {
+        let range = 1..=70_000u128;
+        let expected: Vec<u128> = range
+            .clone()
+            .filter(|&id| is_repeated(id, query.base, query.repeats))
+            .collect();
+
+        assert!(range.clone().all(|id| query.matches(id) == expected.contains(&id)));
+
+        assert_eq!(expected, query.iter(range.clone()).collect::<Vec<_>>());
+        assert_eq!(expected.len() as u128, query.count(range.clone()));
+        assert_eq!(expected.iter().sum::<u128>(), query.sum(range)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_matches() -> miette::Result<()> {
+        assert!(RepeatQuery::exactly(2).matches(1111));
+        assert!(RepeatQuery::exactly(4).matches(1111));
+        assert!(!RepeatQuery::exactly(3).matches(1111));
+        assert!(RepeatQuery::at_least(2).matches(824824824));
+        assert!(RepeatQuery::exactly(2).in_base(2)?.matches(0b1010));
+        assert!(RepeatQuery::exactly(2).in_base(1).is_err());
+        assert!(RepeatQuery::exactly(2).in_base(0).is_err());
+        assert_eq!(16, RepeatQuery::at_least(2).in_base(16)?.base());
+        Ok(())
+    }
+}
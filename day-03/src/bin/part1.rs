@@ -1,4 +1,7 @@
-use day_03::part1::process;
+use day_03::{
+    joltage::{Highlight, explain},
+    part1::process,
+};
 use miette::Context;
 
 #[tracing::instrument]
@@ -6,7 +9,16 @@ fn main() -> miette::Result<()> {
     tracing_subscriber::fmt::init();
 
     let file = include_str!("../../input1.txt");
+
+    // `--explain` shows the batteries picked in every bank, add
+    // `--brackets` when the terminal doesn't do colours
+    let args: Vec<String> = std::env::args().collect();
+    if args.iter().any(|a| a == "--explain") {
+        let highlight = if args.iter().any(|a| a == "--brackets") { Highlight::Brackets } else { Highlight::Ansi };
+        println!("{}", explain(file, 2, highlight).context("explain part 1")?);
+    }
+
     let result = process(file).context("process part 1")?;
     println!("{}", result);
     Ok(())
-}
\ No newline at end of file
+}
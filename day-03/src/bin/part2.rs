@@ -1,4 +1,7 @@
-use day_03::part2::process;
+use day_03::{
+    joltage::{Highlight, explain},
+    part2::process,
+};
 use miette::Context;
 
 #[tracing::instrument]
@@ -6,6 +9,15 @@ fn main() -> miette::Result<()> {
     tracing_subscriber::fmt::init();
 
     let file = include_str!("../../input2.txt");
+
+    // `--explain` shows the batteries picked in every bank, add
+    // `--brackets` when the terminal doesn't do colours
+    let args: Vec<String> = std::env::args().collect();
+    if args.iter().any(|a| a == "--explain") {
+        let highlight = if args.iter().any(|a| a == "--brackets") { Highlight::Brackets } else { Highlight::Ansi };
+        println!("{}", explain(file, 12, highlight).context("explain part 2")?);
+    }
+
     let result = process(file).context("process part 2")?;
     println!("{}", result);
     Ok(())
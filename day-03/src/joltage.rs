@@ -0,0 +1,180 @@
+use std::fmt;
+
+use miette::miette;
+use nom::{
+    IResult, Parser,
+    character::complete::{line_ending, satisfy},
+    multi::{many1, separated_list1},
+};
+
+/// The largest number that can be made from a bank by keeping `k` of its
+/// digits in order. It's a `Number` whenever the value fits in a `u128`,
+/// and kept as its digits otherwise.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Joltage {
+    Number(u128),
+    Digits(String),
+}
+
+impl Joltage {
+    pub fn as_u128(&self) -> Option<u128> {
+        match self {
+            Joltage::Number(n) => Some(*n),
+            Joltage::Digits(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for Joltage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Joltage::Number(n) => write!(f, "{n}"),
+            Joltage::Digits(digits) => write!(f, "{digits}"),
+        }
+    }
+}
+
+/// The batteries picked from a bank, `indices` are positions in the bank
+/// in ascending order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selection {
+    pub indices: Vec<usize>,
+    pub joltage: Joltage,
+}
+
+/// How the picked batteries stand out when rendering a bank.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Highlight {
+    Ansi,
+    Brackets,
+}
+
+pub fn max_subsequence(row: &[i8], k: usize) -> miette::Result<Joltage> {
+    Ok(select_batteries(row, k)?.joltage)
+}
+
+/// Greedy monotonic stack, a digit pushes out every smaller digit before it
+/// for as long as we can still afford to skip digits.
+pub fn select_batteries(row: &[i8], k: usize) -> miette::Result<Selection> {
+    if k > row.len() {
+        return Err(miette!("can't pick {} batteries from a bank of {}", k, row.len()));
+    }
+
+    let mut skips = row.len() - k;
+    let mut stack: Vec<usize> = Vec::with_capacity(row.len());
+
+    for (i, &digit) in row.iter().enumerate() {
+        while skips > 0 && stack.last().is_some_and(|&top| row[top] < digit) {
+            stack.pop();
+            skips -= 1;
+        }
+        stack.push(i);
+    }
+    stack.truncate(k);
+
+    let number = stack
+        .iter()
+        .try_fold(0u128, |acc, &i| acc.checked_mul(10)?.checked_add(row[i] as u128));
+
+    let joltage = match number {
+        Some(n) => Joltage::Number(n),
+        None => Joltage::Digits(stack.iter().map(|&i| digit_char(row[i])).collect()),
+    };
+
+    Ok(Selection { indices: stack, joltage })
+}
+
+/// Write out the bank with the selected batteries highlighted.
+pub fn render(row: &[i8], selection: &Selection, highlight: Highlight) -> String {
+    let mut selected = selection.indices.iter().peekable();
+
+    row.iter()
+        .enumerate()
+        .map(|(i, &digit)| {
+            let c = digit_char(digit);
+            if selected.next_if_eq(&&i).is_none() {
+                return c.to_string();
+            }
+            match highlight {
+                Highlight::Ansi => format!("\x1b[1;32m{c}\x1b[0m"),
+                Highlight::Brackets => format!("[{c}]"),
+            }
+        })
+        .collect()
+}
+
+/// One line per bank with the picked batteries and the resulting joltage.
+pub fn explain(input: &str, k: usize, highlight: Highlight) -> miette::Result<String> {
+    let (_, banks) = batteries(input).map_err(|e| miette!("Parse error: {:?}", e))?;
+
+    banks
+        .iter()
+        .map(|row| {
+            let selection = select_batteries(row, k)?;
+            Ok(format!("{} => {}", render(row, &selection, highlight), selection.joltage))
+        })
+        .collect::<miette::Result<Vec<_>>>()
+        .map(|lines| lines.join("\n"))
+}
+
+fn digit_char(digit: i8) -> char {
+    (b'0' + digit as u8) as char
+}
+
+/// Sum the best `k` digit joltage of every bank.
+pub fn total_joltage(banks: &[Vec<i8>], k: usize) -> miette::Result<u128> {
+    banks.iter().try_fold(0u128, |acc, row| {
+        let joltage = max_subsequence(row, k)?;
+        joltage
+            .as_u128()
+            .and_then(|j| acc.checked_add(j))
+            .ok_or_else(|| miette!("total joltage overflows u128 at bank joltage {}", joltage))
+    })
+}
+
+pub fn batteries(input: &str) -> IResult<&str, Vec<Vec<i8>>> {
+    separated_list1(
+        line_ending,
+        many1(satisfy(|c| c.is_ascii_digit()).map(|c| c.to_digit(10).unwrap() as i8))
+    ).parse(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+
+    #[test]
+    fn test_max_subsequence() -> miette::Result<()> {
+        let row: Vec<i8> = "818181911112111".bytes().map(|b| (b - b'0') as i8).collect();
+        assert_eq!(Joltage::Number(92), max_subsequence(&row, 2)?);
+        assert_eq!(Joltage::Number(888911112111), max_subsequence(&row, 12)?);
+        assert_eq!(Joltage::Number(818181911112111), max_subsequence(&row, 15)?);
+        assert!(max_subsequence(&row, 16).is_err());
+
+        // every combination of 3 digits, the greedy pick has to be the largest
+        let best = row
+            .iter()
+            .combinations(3)
+            .map(|c| c.iter().fold(0u128, |acc, &&d| acc * 10 + d as u128))
+            .max();
+        assert_eq!(best, max_subsequence(&row, 3)?.as_u128());
+
+        let long = vec![9i8; 40];
+        assert_eq!(Joltage::Digits("9".repeat(40)), max_subsequence(&long, 40)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_explain() -> miette::Result<()> {
+        let input = "987654321111111
+811111111111119";
+        assert_eq!("[9][8]7654321111111 => 98\n[8]1111111111111[9] => 89", explain(input, 2, Highlight::Brackets)?);
+
+        let row: Vec<i8> = "234234234234278".bytes().map(|b| (b - b'0') as i8).collect();
+        let selection = select_batteries(&row, 12)?;
+        assert_eq!(vec![2, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14], selection.indices);
+        assert_eq!(Joltage::Number(434234234278), selection.joltage);
+        Ok(())
+    }
+}
@@ -6,6 +6,22 @@ fn main() -> miette::Result<()> {
     tracing_subscriber::fmt::init();
 
     let file = include_str!("../../input2.txt");
+
+    // with the `record` feature, `--play [fps]`, `--gif <file>` and
+    // `--png <dir>` show the rolls being removed
+    #[cfg(feature = "record")]
+    {
+        use day_04::{grid::{RollGrid, Rules}, record::record_peel};
+        use frame_recorder::{Output, Recorder};
+
+        let output = Output::from_args(std::env::args())?;
+        if output.is_requested() {
+            let mut recorder = Recorder::new();
+            record_peel(&RollGrid::parse(file, Rules::default())?, &mut recorder)?;
+            output.write(&recorder).context("record part 2")?;
+        }
+    }
+
     let result = process(file).context("process part 2")?;
     println!("{}", result);
     Ok(())
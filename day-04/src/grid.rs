@@ -0,0 +1,371 @@
+use std::collections::VecDeque;
+
+use glam::IVec2;
+use miette::miette;
+use nom::{
+    IResult, Parser,
+    character::complete::{anychar, line_ending},
+    multi::{many0, separated_list1},
+};
+use nom_locate::{LocatedSpan, position};
+
+pub const MOORE: [IVec2; 8] = [
+    IVec2::new(1, 0),
+    IVec2::new(-1, 0),
+    IVec2::new(0, 1),
+    IVec2::new(0, -1),
+    IVec2::new(1, 1),
+    IVec2::new(1, -1),
+    IVec2::new(-1, 1),
+    IVec2::new(-1, -1),
+];
+
+pub const VON_NEUMANN: [IVec2; 4] = [
+    IVec2::new(1, 0),
+    IVec2::new(-1, 0),
+    IVec2::new(0, 1),
+    IVec2::new(0, -1),
+];
+
+/// Which cells around a roll count as its neighbours.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Neighbourhood {
+    VonNeumann,
+    Moore,
+    Custom(Vec<IVec2>),
+}
+
+/// What lies beyond the edge of the map. `Bounded` is empty floor,
+/// `Padded` is a wall of rolls that never goes away, and `Toroidal`
+/// wraps around to the other side.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edges {
+    Bounded,
+    Toroidal,
+    Padded,
+}
+
+/// The forklift rules, a roll is accessible when it has fewer than
+/// `threshold` neighbouring rolls.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rules {
+    pub neighbourhood: Neighbourhood,
+    pub threshold: u16,
+    pub edges: Edges,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self { neighbourhood: Neighbourhood::Moore, threshold: 4, edges: Edges::Bounded }
+    }
+}
+
+impl Neighbourhood {
+    pub fn offsets(&self) -> &[IVec2] {
+        match self {
+            Neighbourhood::VonNeumann => &VON_NEUMANN,
+            Neighbourhood::Moore => &MOORE,
+            Neighbourhood::Custom(offsets) => offsets,
+        }
+    }
+}
+
+/// Dense grid of paper rolls, one bit per cell, with the number of
+/// neighbouring rolls kept next to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RollGrid {
+    pub width: i32,
+    pub height: i32,
+    pub rules: Rules,
+    rolls: Vec<u64>,
+    neighbours: Vec<u16>,
+}
+
+/// Result of peeling away accessible rolls until none are left.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Peel {
+    width: i32,
+    // wave in which each cell was removed, 0 for cells that stay
+    waves: Vec<u32>,
+    pub removed: usize,
+    pub rounds: u32,
+}
+
+impl RollGrid {
+    pub fn parse(input: &str, rules: Rules) -> miette::Result<Self> {
+        let (_, rolls) = read_map(Span::new(input)).map_err(|e| miette!("parse failed {}", e))?;
+
+        let height = input.lines().count() as i32;
+        let width = input.lines().map(|l| l.len() as i32).max().unwrap_or(0);
+        Ok(Self::new(width, height, rolls, rules))
+    }
+
+    pub fn new(width: i32, height: i32, rolls: impl IntoIterator<Item = IVec2>, rules: Rules) -> Self {
+        let cells = (width * height) as usize;
+        let mut grid = Self {
+            width,
+            height,
+            rules,
+            rolls: vec![0; cells.div_ceil(64)],
+            neighbours: vec![0; cells],
+        };
+
+        for pos in rolls {
+            let i = grid.index(pos).expect("roll inside the grid");
+            grid.rolls[i / 64] |= 1 << (i % 64);
+        }
+        for i in grid.roll_indices().collect::<Vec<_>>() {
+            grid.neighbours[i] = grid.count_neighbours(i);
+        }
+        grid
+    }
+
+    pub fn contains(&self, pos: IVec2) -> bool {
+        self.index(pos).is_some_and(|i| self.has_roll(i))
+    }
+
+    /// Put a roll at `pos`, returns false when there already was one.
+    pub fn insert(&mut self, pos: IVec2) -> miette::Result<bool> {
+        let i = self.index(pos).ok_or_else(|| miette!("{} is outside the map", pos))?;
+        if self.has_roll(i) {
+            return Ok(false);
+        }
+
+        self.rolls[i / 64] |= 1 << (i % 64);
+        self.neighbours[i] = self.count_neighbours(i);
+        for n in self.counted_by(i).filter(|&n| n != i).collect::<Vec<_>>() {
+            self.neighbours[n] += 1;
+        }
+        Ok(true)
+    }
+
+    /// Take away the roll at `pos`, returns false when there was none.
+    pub fn remove(&mut self, pos: IVec2) -> miette::Result<bool> {
+        let i = self.index(pos).ok_or_else(|| miette!("{} is outside the map", pos))?;
+        if !self.has_roll(i) {
+            return Ok(false);
+        }
+
+        self.rolls[i / 64] &= !(1 << (i % 64));
+        self.neighbours[i] = 0;
+        for n in self.counted_by(i).collect::<Vec<_>>() {
+            self.neighbours[n] -= 1;
+        }
+        Ok(true)
+    }
+
+    pub fn rolls(&self) -> impl Iterator<Item = IVec2> + '_ {
+        self.roll_indices().map(|i| self.pos(i))
+    }
+
+    pub fn len(&self) -> usize {
+        self.rolls.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Rolls a forklift can reach right now.
+    pub fn accessible(&self) -> impl Iterator<Item = IVec2> + '_ {
+        self.roll_indices()
+            .filter(|&i| self.is_accessible(i))
+            .map(|i| self.pos(i))
+    }
+
+    /// Keep removing accessible rolls. Only the neighbours of a removed roll
+    /// can become accessible, so those are the only ones we look at again.
+    pub fn peel(&self) -> Peel {
+        let mut neighbours = self.neighbours.clone();
+        let mut waves = vec![0u32; neighbours.len()];
+        let mut queue: VecDeque<usize> = VecDeque::new();
+
+        let threshold = self.rules.threshold;
+        for i in self.roll_indices().filter(|&i| neighbours[i] < threshold) {
+            waves[i] = 1;
+            queue.push_back(i);
+        }
+
+        let mut removed = 0;
+        let mut rounds = 0;
+        while let Some(i) = queue.pop_front() {
+            removed += 1;
+            rounds = waves[i];
+
+            for n in self.counted_by(i) {
+                neighbours[n] -= 1;
+                if waves[n] == 0 && neighbours[n] < threshold {
+                    waves[n] = waves[i] + 1;
+                    queue.push_back(n);
+                }
+            }
+        }
+
+        Peel { width: self.width, waves, removed, rounds }
+    }
+
+    pub(crate) fn roll_indices(&self) -> impl Iterator<Item = usize> + '_ {
+        self.rolls.iter().enumerate().flat_map(|(w, &word)| {
+            let mut bits = word;
+            std::iter::from_fn(move || {
+                if bits == 0 {
+                    return None;
+                }
+                let bit = bits.trailing_zeros() as usize;
+                bits &= bits - 1;
+                Some(w * 64 + bit)
+            })
+        })
+    }
+
+    pub(crate) fn is_accessible(&self, i: usize) -> bool {
+        self.has_roll(i) && self.neighbours[i] < self.rules.threshold
+    }
+
+    // rolls around `i`, plus the padding when looking over the edge
+    fn count_neighbours(&self, i: usize) -> u16 {
+        self.count_matching(i, |n| self.has_roll(n))
+    }
+
+    // neighbours of `i` for which `include` holds, cells over the edge
+    // count when the map is padded
+    pub(crate) fn count_matching(&self, i: usize, include: impl Fn(usize) -> bool) -> u16 {
+        let pos = self.pos(i);
+        let offsets = self.rules.neighbourhood.offsets();
+        offsets
+            .iter()
+            .filter(|&&d| match self.resolve(pos + d) {
+                Some(n) => include(n),
+                None => self.rules.edges == Edges::Padded,
+            })
+            .count() as u16
+    }
+
+    pub(crate) fn neighbours_of(&self, i: usize) -> impl Iterator<Item = usize> + '_ {
+        let pos = self.pos(i);
+        self.rules
+            .neighbourhood
+            .offsets()
+            .iter()
+            .filter_map(move |&d| self.resolve(pos + d))
+            .filter(|&n| self.has_roll(n))
+    }
+
+    // the rolls that have `i` in their neighbourhood, these are the same as
+    // its neighbours unless a custom neighbourhood isn't symmetric
+    pub(crate) fn counted_by(&self, i: usize) -> impl Iterator<Item = usize> + '_ {
+        let pos = self.pos(i);
+        self.rules
+            .neighbourhood
+            .offsets()
+            .iter()
+            .filter_map(move |&d| self.resolve(pos - d))
+            .filter(|&n| self.has_roll(n))
+    }
+
+    fn resolve(&self, pos: IVec2) -> Option<usize> {
+        match self.rules.edges {
+            Edges::Toroidal if self.width > 0 && self.height > 0 => {
+                let wrapped = IVec2::new(pos.x.rem_euclid(self.width), pos.y.rem_euclid(self.height));
+                self.index(wrapped)
+            }
+            _ => self.index(pos),
+        }
+    }
+
+    pub(crate) fn has_roll(&self, i: usize) -> bool {
+        self.rolls[i / 64] & (1 << (i % 64)) != 0
+    }
+
+    pub(crate) fn index(&self, pos: IVec2) -> Option<usize> {
+        let inside = pos.x >= 0 && pos.x < self.width && pos.y >= 0 && pos.y < self.height;
+        inside.then(|| (pos.y * self.width + pos.x) as usize)
+    }
+
+    pub(crate) fn pos(&self, i: usize) -> IVec2 {
+        IVec2::new(i as i32 % self.width, i as i32 / self.width)
+    }
+}
+
+impl Peel {
+    /// Wave in which the roll at `pos` was removed, starting at 1.
+    pub fn wave(&self, pos: IVec2) -> Option<u32> {
+        if pos.x < 0 || pos.x >= self.width || pos.y < 0 {
+            return None;
+        }
+        let i = (pos.y * self.width + pos.x) as usize;
+        self.waves.get(i).copied().filter(|&w| w > 0)
+    }
+
+    /// The removed rolls grouped per wave, in removal order.
+    pub fn waves(&self) -> Vec<Vec<IVec2>> {
+        let mut waves = vec![Vec::new(); self.rounds as usize];
+        for (i, &w) in self.waves.iter().enumerate().filter(|&(_, &w)| w > 0) {
+            waves[w as usize - 1].push(IVec2::new(i as i32 % self.width, i as i32 / self.width));
+        }
+        waves
+    }
+}
+
+pub fn read_map(input: Span) -> IResult<Span, Vec<IVec2>> {
+    let (input, rows) = separated_list1(line_ending, many0(rolls)).parse(input)?;
+    Ok((input, rows.into_iter().flatten().flatten().collect()))
+}
+
+fn rolls(input: Span) -> IResult<Span, Option<IVec2>> {
+    let (input, pos) = position(input)?;
+    let x = pos.get_column() as i32 - 1;
+    let y = pos.location_line() as i32 - 1;
+    let (input, c) = anychar.parse(input)?;
+    if c == '@' {
+        Ok((input, Some(IVec2::new(x, y))))
+    } else {
+        Ok((input, None))
+    }
+}
+
+pub type Span<'a> = LocatedSpan<&'a str>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_peel_waves() -> miette::Result<()> {
+        let grid = RollGrid::parse("@@@\n@@@\n@@@\n", Rules::default())?;
+        let peel = grid.peel();
+
+        // the corners go first, then the edges, then the middle
+        assert_eq!(4, grid.accessible().count());
+        assert_eq!(Some(1), peel.wave(IVec2::new(0, 0)));
+        assert_eq!(Some(2), peel.wave(IVec2::new(1, 0)));
+        assert_eq!(Some(3), peel.wave(IVec2::new(1, 1)));
+        assert_eq!(vec![4, 4, 1], peel.waves().iter().map(Vec::len).collect::<Vec<_>>());
+        assert_eq!(9, peel.removed);
+        Ok(())
+    }
+
+    #[test]
+    fn test_rules() -> miette::Result<()> {
+        let input = "@@@\n@@@\n@@@\n";
+        let rules = |neighbourhood, threshold, edges| Rules { neighbourhood, threshold, edges };
+
+        // only the corners have fewer than 3 orthogonal neighbours
+        let grid = RollGrid::parse(input, rules(Neighbourhood::VonNeumann, 3, Edges::Bounded))?;
+        assert_eq!(4, grid.accessible().count());
+
+        // nothing has an edge when the map wraps or is walled in
+        let grid = RollGrid::parse(input, rules(Neighbourhood::Moore, 8, Edges::Toroidal))?;
+        assert_eq!(0, grid.peel().removed);
+        let grid = RollGrid::parse(input, rules(Neighbourhood::Moore, 8, Edges::Padded))?;
+        assert_eq!(0, grid.peel().removed);
+
+        // a roll only looking right gets freed once its right neighbour is gone
+        let custom = Neighbourhood::Custom(vec![IVec2::X]);
+        let grid = RollGrid::parse("@@@\n", rules(custom, 1, Edges::Bounded))?;
+        let peel = grid.peel();
+        assert_eq!(Some(3), peel.wave(IVec2::new(0, 0)));
+        assert_eq!(Some(1), peel.wave(IVec2::new(2, 0)));
+        Ok(())
+    }
+}
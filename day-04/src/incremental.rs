@@ -0,0 +1,200 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use glam::IVec2;
+
+use crate::grid::RollGrid;
+
+/// Answers after an edit: how many rolls are accessible right now and how
+/// many would be removed by peeling the whole map.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Counts {
+    pub accessible: usize,
+    pub removed: usize,
+}
+
+/// A map that can be edited one roll at a time.
+///
+/// Peeling always ends in the same core of rolls, the largest set in which
+/// every roll has enough neighbours inside the set. Removing a roll can only
+/// shrink that core and adding one can only grow it, so an edit only has to
+/// look at the rolls around it instead of peeling the whole map again.
+#[derive(Debug, Clone)]
+pub struct IncrementalMap {
+    grid: RollGrid,
+    core: Vec<bool>,
+    rolls: usize,
+    core_len: usize,
+    accessible: usize,
+}
+
+impl IncrementalMap {
+    pub fn new(grid: RollGrid) -> Self {
+        let peel = grid.peel();
+        let mut core = vec![false; (grid.width * grid.height) as usize];
+        for i in grid.roll_indices() {
+            core[i] = peel.wave(grid.pos(i)).is_none();
+        }
+
+        Self {
+            rolls: grid.len(),
+            core_len: core.iter().filter(|&&c| c).count(),
+            accessible: grid.accessible().count(),
+            core,
+            grid,
+        }
+    }
+
+    pub fn grid(&self) -> &RollGrid {
+        &self.grid
+    }
+
+    pub fn counts(&self) -> Counts {
+        Counts { accessible: self.accessible, removed: self.rolls - self.core_len }
+    }
+
+    pub fn add(&mut self, pos: IVec2) -> miette::Result<Counts> {
+        let before = self.accessible_around(pos);
+        if !self.grid.insert(pos)? {
+            return Ok(self.counts());
+        }
+        self.rolls += 1;
+        self.accessible = self.accessible + self.accessible_around(pos) - before;
+
+        self.grow(self.grid.index(pos).unwrap());
+        Ok(self.counts())
+    }
+
+    pub fn remove(&mut self, pos: IVec2) -> miette::Result<Counts> {
+        let before = self.accessible_around(pos);
+        if !self.grid.remove(pos)? {
+            return Ok(self.counts());
+        }
+        self.rolls -= 1;
+        self.accessible = self.accessible + self.accessible_around(pos) - before;
+
+        let i = self.grid.index(pos).unwrap();
+        if self.core[i] {
+            self.core[i] = false;
+            self.core_len -= 1;
+            self.shrink(i);
+        }
+        Ok(self.counts())
+    }
+
+    // the roll at `pos` and everything counting it are the only ones whose
+    // accessibility an edit can change
+    fn accessible_around(&self, pos: IVec2) -> usize {
+        let Some(i) = self.grid.index(pos) else {
+            return 0;
+        };
+        let mut cells: Vec<usize> = self.grid.counted_by(i).chain([i]).collect();
+        cells.sort_unstable();
+        cells.dedup();
+        cells.iter().filter(|&&n| self.grid.is_accessible(n)).count()
+    }
+
+    // `removed` left the core, peel whatever no longer has enough core
+    // neighbours because of it
+    fn shrink(&mut self, removed: usize) {
+        let threshold = self.grid.rules.threshold;
+        let mut queue = VecDeque::from([removed]);
+
+        while let Some(i) = queue.pop_front() {
+            for n in self.grid.counted_by(i).collect::<Vec<_>>() {
+                if self.core[n] && self.grid.count_matching(n, |m| self.core[m]) < threshold {
+                    self.core[n] = false;
+                    self.core_len -= 1;
+                    queue.push_back(n);
+                }
+            }
+        }
+    }
+
+    // a new roll can only pull in the non core rolls connected to it, peel
+    // those against the existing core and keep whatever survives
+    fn grow(&mut self, added: usize) {
+        let threshold = self.grid.rules.threshold;
+
+        let mut candidates = HashSet::from([added]);
+        let mut queue = VecDeque::from([added]);
+        while let Some(i) = queue.pop_front() {
+            for n in self.grid.neighbours_of(i).chain(self.grid.counted_by(i)) {
+                if !self.core[n] && candidates.insert(n) {
+                    queue.push_back(n);
+                }
+            }
+        }
+
+        let mut counts: HashMap<usize, u16> = candidates
+            .iter()
+            .map(|&i| (i, self.grid.count_matching(i, |m| self.core[m] || candidates.contains(&m))))
+            .collect();
+
+        let mut queue: VecDeque<usize> = counts.iter().filter(|&(_, &c)| c < threshold).map(|(&i, _)| i).collect();
+        let mut peeled: HashSet<usize> = queue.iter().copied().collect();
+        while let Some(i) = queue.pop_front() {
+            for n in self.grid.counted_by(i) {
+                if peeled.contains(&n) {
+                    continue;
+                }
+                if let Some(count) = counts.get_mut(&n) {
+                    *count -= 1;
+                    if *count < threshold {
+                        peeled.insert(n);
+                        queue.push_back(n);
+                    }
+                }
+            }
+        }
+
+        for i in candidates.difference(&peeled) {
+            self.core[*i] = true;
+            self.core_len += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::{Edges, Neighbourhood, Rules};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(Rules::default())]
+    #[case(Rules { neighbourhood: Neighbourhood::VonNeumann, threshold: 2, edges: Edges::Toroidal })]
+    #[case(Rules { neighbourhood: Neighbourhood::Moore, threshold: 5, edges: Edges::Padded })]
+    #[case(Rules {
+        neighbourhood: Neighbourhood::Custom(vec![IVec2::X, IVec2::new(1, 1), IVec2::NEG_Y]),
+        threshold: 2,
+        edges: Edges::Bounded,
+    })]
+    fn test_edits_match_recomputation(#[case] rules: Rules) -> miette::Result<()> {
+        let input = "..@@.@@@@.
+@@@.@.@.@@
+@@@@@.@.@@
+@.@@@@..@.
+@@.@@@@.@@
+.@@@@@@@.@
+.@.@.@.@@@
+@.@@@.@@@@
+.@@@@@@@@.
+@.@.@@@.@.
+";
+        let mut map = IncrementalMap::new(RollGrid::parse(input, rules.clone())?);
+
+        // walk the map in a fixed pseudo random order, flipping cells
+        let mut seed = 17u32;
+        for _ in 0..400 {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            let pos = IVec2::new((seed >> 8) as i32 % 10, (seed >> 16) as i32 % 10);
+
+            let counts = if map.grid().contains(pos) { map.remove(pos)? } else { map.add(pos)? };
+
+            let fresh = RollGrid::new(10, 10, map.grid().rolls().collect::<Vec<_>>(), rules.clone());
+            let expected = Counts { accessible: fresh.accessible().count(), removed: fresh.peel().removed };
+            assert_eq!(expected, counts, "after flipping {pos}");
+        }
+        Ok(())
+    }
+}
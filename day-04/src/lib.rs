@@ -1,2 +1,6 @@
+pub mod grid;
+pub mod incremental;
 pub mod part1;
 pub mod part2;
+#[cfg(feature = "record")]
+pub mod record;
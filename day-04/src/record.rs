@@ -0,0 +1,46 @@
+use frame_recorder::{Frame, Recorder};
+use glam::IVec2;
+
+use crate::grid::{Peel, RollGrid};
+
+/// Peel the grid and record it wave by wave, the rolls taken in a wave
+/// show up as `x` before they disappear in the next frame.
+pub fn record_peel(grid: &RollGrid, recorder: &mut Recorder) -> miette::Result<Peel> {
+    let mut frame = Frame::new(grid.width as usize, grid.height as usize, '.');
+    for pos in grid.rolls() {
+        frame.set(pos.x as usize, pos.y as usize, '@');
+    }
+    recorder.push(frame);
+
+    let peel = grid.peel();
+    let mut previous: Vec<IVec2> = Vec::new();
+    for wave in peel.waves() {
+        let gone = previous.iter().map(|p| (p.x as usize, p.y as usize, '.'));
+        let taken = wave.iter().map(|p| (p.x as usize, p.y as usize, 'x'));
+        recorder.push_diff(gone.chain(taken))?;
+        previous = wave;
+    }
+    recorder.push_diff(previous.iter().map(|p| (p.x as usize, p.y as usize, '.')))?;
+
+    Ok(peel)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Rules;
+
+    #[test]
+    fn test_record_peel() -> miette::Result<()> {
+        let grid = RollGrid::parse("@@@\n@@@\n@@@\n", Rules::default())?;
+        let mut recorder = Recorder::new();
+        record_peel(&grid, &mut recorder)?;
+
+        let frames: Vec<String> = recorder.frames().iter().map(|f| f.to_string()).collect();
+        assert_eq!("@@@\n@@@\n@@@\n", frames[0]);
+        assert_eq!("x@x\n@@@\nx@x\n", frames[1]);
+        assert_eq!(".x.\nx@x\n.x.\n", frames[2]);
+        assert_eq!("...\n...\n...\n", frames[4]);
+        Ok(())
+    }
+}
@@ -1,12 +1,48 @@
-use day_05::part1::process;
-use miette::Context;
+use std::io::{self, BufReader, Write};
+
+use day_05::{part1::process, report::report, stream::Checks};
+use miette::{Context, IntoDiagnostic};
 
 #[tracing::instrument]
 fn main() -> miette::Result<()> {
     tracing_subscriber::fmt::init();
 
+    // `--stream [file]` checks a database of any size from the file or stdin,
+    // printing each ingredient as soon as it's read
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(i) = args.iter().position(|a| a == "--stream") {
+        let fresh = match args.get(i + 1) {
+            Some(path) => {
+                let file = std::fs::File::open(path).into_diagnostic().context("open database")?;
+                stream(BufReader::new(file))?
+            }
+            None => stream(io::stdin().lock())?,
+        };
+        println!("{}", fresh);
+        return Ok(());
+    }
+
     let file = include_str!("../../input1.txt");
+
+    // `--report` prints the audit as a table, `--json` as json
+    if args.iter().any(|a| a == "--json") {
+        println!("{}", report(file).context("report part 1")?.to_json()?);
+    } else if args.iter().any(|a| a == "--report") {
+        print!("{}", report(file).context("report part 1")?);
+    }
+
     let result = process(file).context("process part 1")?;
     println!("{}", result);
     Ok(())
-}
\ No newline at end of file
+}
+
+fn stream(reader: impl io::BufRead) -> miette::Result<usize> {
+    let mut out = io::stdout().lock();
+    let mut fresh = 0;
+    for check in Checks::new(reader)? {
+        let (id, is_fresh) = check?;
+        fresh += is_fresh as usize;
+        writeln!(out, "{} {}", id, if is_fresh { "fresh" } else { "spoiled" }).into_diagnostic()?;
+    }
+    Ok(fresh)
+}
@@ -1,4 +1,5 @@
 use std::ops::RangeInclusive;
+use interval_set::IntervalSet;
 use nom::{
     IResult, Parser,
     bytes::complete::tag,
@@ -11,25 +12,20 @@ use nom::{
 pub fn process(input: &str) -> miette::Result<String> {
     let (_, (ranges, ingredients)) = parse_input(input).map_err(|e| miette::miette!("Parse error: {:?}", e))?;
 
-    let fresh = ingredients.iter().fold(0, |acc, &ingredient| {
-        if ranges.iter().any(|range| range.contains(&ingredient)) {
-            acc + 1
-        } else {
-            acc
-        }
-    });
+    let fresh_ranges: IntervalSet<u64> = ranges.into_iter().collect();
+    let fresh = ingredients.iter().filter(|&&ingredient| fresh_ranges.contains(ingredient)).count();
 
     Ok(fresh.to_string())
 }
 
-fn parse_input(input: &str) -> IResult<&str, (Vec<RangeInclusive<u64>>, Vec<u64>)> {
+pub(crate) fn parse_input(input: &str) -> IResult<&str, (Vec<RangeInclusive<u64>>, Vec<u64>)> {
     let (input, ranges) = separated_list1(line_ending, parse_range).parse(input)?;
     let (input, _) = many0(line_ending).parse(input)?;
     let (input, ingredients) = separated_list1(line_ending, u64).parse(input)?;
     Ok((input, (ranges, ingredients)))
 }
 
-fn parse_range(input: &str) -> IResult<&str, RangeInclusive<u64>> {
+pub(crate) fn parse_range(input: &str) -> IResult<&str, RangeInclusive<u64>> {
     separated_pair(u64, tag("-"), u64)
         .map(|(first, last)| first..=last)
         .parse(input)
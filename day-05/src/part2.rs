@@ -1,4 +1,5 @@
 use std::ops::RangeInclusive;
+use interval_set::IntervalSet;
 use nom::{
     IResult, Parser,
     bytes::complete::tag,
@@ -11,41 +12,9 @@ use nom::{
 pub fn process(input: &str) -> miette::Result<String> {
     let (_, ranges) = parse_input(input).map_err(|e| miette::miette!("Parse error: {:?}", e))?;
 
-    let sum = implode_ranges(ranges).iter().fold(0u64, |acc, range| {
-        acc + (range.end() - range.start() + 1)
-    });
+    let fresh: IntervalSet<u64> = ranges.into_iter().collect();
 
-    Ok(sum.to_string())
-}
-
-fn implode_ranges(mut ranges: Vec<RangeInclusive<u64>>) -> Vec<RangeInclusive<u64>> {
-    ranges.sort_by(|a, b| a.start().cmp(b.start()));
-
-    let mut imploded_ranges = Vec::new();
-    imploded_ranges.push(ranges[0].clone());
-
-    for range in ranges[1..].iter() {
-        let previous = imploded_ranges.last().unwrap();
-
-        match () {
-            _ if range.start() >= previous.start() && range.end() <= previous.end() => {
-                continue;
-            }
-            _ if range.start() <= previous.end() => {
-                let last = imploded_ranges.pop().unwrap();
-                imploded_ranges.push(merge_ranges(&last, range));
-            }
-            _ => {
-                imploded_ranges.push(range.clone());
-            }
-        }
-    }
-
-    imploded_ranges
-}
-
-fn merge_ranges(a: &RangeInclusive<u64>, b: &RangeInclusive<u64>) -> RangeInclusive<u64> {
-    *a.start().min(b.start())..=*a.end().max(b.end())
+    Ok(fresh.len().to_string())
 }
 
 fn parse_input(input: &str) -> IResult<&str, Vec<RangeInclusive<u64>>> {
@@ -81,4 +50,10 @@ mod tests {
         assert_eq!("14", process(input)?);
         Ok(())
     }
+
+    #[test]
+    fn test_adjacent_ranges() -> miette::Result<()> {
+        assert_eq!("6", process("6-8\n3-5\n")?);
+        Ok(())
+    }
 }
@@ -0,0 +1,176 @@
+use std::{fmt, ops::RangeInclusive};
+
+use interval_set::IntervalSet;
+use itertools::Itertools;
+use miette::{IntoDiagnostic, miette};
+use serde::Serialize;
+
+use crate::part1::parse_input;
+
+/// Inventory audit of the database: which ranges make each ingredient
+/// fresh, and how the ranges themselves overlap.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Report {
+    pub ingredients: Vec<IngredientMatch>,
+    pub ranges: Vec<RangeStats>,
+    pub blocks: Vec<Block>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct IngredientMatch {
+    pub id: u64,
+    pub fresh: bool,
+    /// Indices into the original ranges, in input order.
+    pub covered_by: Vec<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RangeStats {
+    pub range: RangeInclusive<u64>,
+    pub matches: usize,
+    /// Another range that holds all of this one, if any.
+    pub redundant_in: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Block {
+    pub range: RangeInclusive<u64>,
+    pub size: u128,
+}
+
+impl Report {
+    pub fn new(ranges: &[RangeInclusive<u64>], ingredients: &[u64]) -> Self {
+        let fresh: IntervalSet<u64> = ranges.iter().cloned().collect();
+
+        let ingredients: Vec<IngredientMatch> = ingredients
+            .iter()
+            .map(|&id| {
+                // only go looking for the ranges when the merged set says it's in one
+                let covered_by = if fresh.contains(id) {
+                    ranges.iter().positions(|r| r.contains(&id)).collect()
+                } else {
+                    Vec::new()
+                };
+                IngredientMatch { id, fresh: !covered_by.is_empty(), covered_by }
+            })
+            .collect();
+
+        let ranges = ranges
+            .iter()
+            .enumerate()
+            .map(|(i, range)| RangeStats {
+                range: range.clone(),
+                matches: ingredients.iter().filter(|m| m.covered_by.contains(&i)).count(),
+                redundant_in: redundant_in(ranges, i),
+            })
+            .collect();
+
+        let blocks = fresh
+            .iter()
+            .map(|range| Block { size: range.end().abs_diff(*range.start()) as u128 + 1, range })
+            .collect();
+
+        Self { ingredients, ranges, blocks }
+    }
+
+    pub fn unmatched(&self) -> impl Iterator<Item = &RangeStats> {
+        self.ranges.iter().filter(|r| r.matches == 0)
+    }
+
+    pub fn redundant(&self) -> impl Iterator<Item = &RangeStats> {
+        self.ranges.iter().filter(|r| r.redundant_in.is_some())
+    }
+
+    pub fn to_json(&self) -> miette::Result<String> {
+        serde_json::to_string_pretty(self).into_diagnostic()
+    }
+}
+
+// a range is redundant when another one holds all of it, of two identical
+// ranges only the later one counts as redundant
+fn redundant_in(ranges: &[RangeInclusive<u64>], i: usize) -> Option<usize> {
+    let range = &ranges[i];
+    ranges.iter().enumerate().position(|(j, other)| {
+        j != i
+            && other.start() <= range.start()
+            && range.end() <= other.end()
+            && (other != range || j < i)
+    })
+}
+
+pub fn report(input: &str) -> miette::Result<Report> {
+    let (_, (ranges, ingredients)) = parse_input(input).map_err(|e| miette!("Parse error: {:?}", e))?;
+    Ok(Report::new(&ranges, &ingredients))
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show = |r: &RangeInclusive<u64>| format!("{}-{}", r.start(), r.end());
+
+        writeln!(f, "{:<20} {:<6} ranges", "ingredient", "fresh")?;
+        for m in &self.ingredients {
+            let ranges: Vec<String> = m.covered_by.iter().map(|&i| show(&self.ranges[i].range)).collect();
+            let ranges = if ranges.is_empty() { "-".to_string() } else { ranges.join(", ") };
+            writeln!(f, "{:<20} {:<6} {}", m.id, if m.fresh { "yes" } else { "no" }, ranges)?;
+        }
+
+        writeln!(f)?;
+        writeln!(f, "{:<41} {:<8} notes", "range", "matches")?;
+        for stats in &self.ranges {
+            let mut notes = Vec::new();
+            if stats.matches == 0 {
+                notes.push("unmatched".to_string());
+            }
+            if let Some(j) = stats.redundant_in {
+                notes.push(format!("redundant, inside {}", show(&self.ranges[j].range)));
+            }
+            let row = format!("{:<41} {:<8} {}", show(&stats.range), stats.matches, notes.join(", "));
+            writeln!(f, "{}", row.trim_end())?;
+        }
+
+        writeln!(f)?;
+        writeln!(f, "{:<41} size", "merged block")?;
+        for block in &self.blocks {
+            writeln!(f, "{:<41} {}", show(&block.range), block.size)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report() -> miette::Result<()> {
+        let input = "3-5
+10-14
+16-20
+12-18
+17-19
+30-31
+
+1
+5
+8
+11
+17
+32
+";
+        let report = report(input)?;
+
+        let covered: Vec<(u64, Vec<usize>)> = report.ingredients.iter().map(|m| (m.id, m.covered_by.clone())).collect();
+        assert_eq!(
+            vec![(1, vec![]), (5, vec![0]), (8, vec![]), (11, vec![1]), (17, vec![2, 3, 4]), (32, vec![])],
+            covered
+        );
+        assert_eq!(vec![30..=31], report.unmatched().map(|r| r.range.clone()).collect::<Vec<_>>());
+        assert_eq!(vec![Some(2)], report.redundant().map(|r| r.redundant_in).collect::<Vec<_>>());
+        assert_eq!(vec![3, 11, 2], report.blocks.iter().map(|b| b.size).collect::<Vec<_>>());
+
+        let json: serde_json::Value = serde_json::from_str(&report.to_json()?).into_diagnostic()?;
+        assert_eq!(json["ranges"][4]["redundant_in"], 2);
+        assert_eq!(json["blocks"][1]["range"]["start"], 10);
+        Ok(())
+    }
+}
@@ -0,0 +1,113 @@
+use std::io::BufRead;
+
+use interval_set::IntervalSet;
+use miette::{IntoDiagnostic, miette};
+use nom::{Parser, combinator::all_consuming};
+
+use crate::part1::parse_range;
+
+/// Fresh ranges read from the first section of the database, the reader is
+/// left at the first ingredient ID.
+pub fn read_ranges(reader: &mut impl BufRead) -> miette::Result<IntervalSet<u64>> {
+    let mut fresh = IntervalSet::new();
+    let mut ranges = Vec::new();
+    let mut line = String::new();
+
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).into_diagnostic()? == 0 || line.trim().is_empty() {
+            break;
+        }
+        let (_, range) = all_consuming(parse_range)
+            .parse(line.trim())
+            .map_err(|e| miette!("bad range {:?}: {:?}", line.trim(), e))?;
+        ranges.push(range);
+
+        // merge as we go so a long range section doesn't pile up either
+        if ranges.len() >= 1024 {
+            fresh.extend(ranges.drain(..));
+        }
+    }
+    fresh.extend(ranges);
+    Ok(fresh)
+}
+
+/// Checks the ingredient IDs one line at a time, so memory stays the same
+/// however many IDs follow.
+#[derive(Debug)]
+pub struct Checks<R> {
+    fresh: IntervalSet<u64>,
+    reader: R,
+    line: String,
+    line_number: usize,
+}
+
+impl<R: BufRead> Checks<R> {
+    pub fn new(mut reader: R) -> miette::Result<Self> {
+        let fresh = read_ranges(&mut reader)?;
+        Ok(Self { fresh, reader, line: String::new(), line_number: 0 })
+    }
+
+    pub fn fresh(&self) -> &IntervalSet<u64> {
+        &self.fresh
+    }
+}
+
+impl<R: BufRead> Iterator for Checks<R> {
+    /// The ingredient ID and whether it is fresh.
+    type Item = miette::Result<(u64, bool)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.line.clear();
+            match self.reader.read_line(&mut self.line) {
+                Ok(0) => return None,
+                Ok(_) => {}
+                Err(e) => return Some(Err(e).into_diagnostic()),
+            }
+            self.line_number += 1;
+
+            let id = self.line.trim();
+            if id.is_empty() {
+                continue;
+            }
+            return Some(
+                id.parse::<u64>()
+                    .map(|id| (id, self.fresh.contains(id)))
+                    .map_err(|e| miette!("bad ingredient {:?} on line {} of the IDs: {}", id, self.line_number, e)),
+            );
+        }
+    }
+}
+
+/// Number of fresh ingredients, without holding on to any of them.
+pub fn count_fresh(reader: impl BufRead) -> miette::Result<usize> {
+    Checks::new(reader)?.try_fold(0, |count, check| Ok(count + check?.1 as usize))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stream() -> miette::Result<()> {
+        let input = "3-5
+10-14
+16-20
+12-18
+
+1
+5
+8
+11
+17
+32
+";
+        let checks = Checks::new(input.as_bytes())?.collect::<miette::Result<Vec<_>>>()?;
+        assert_eq!(vec![(1, false), (5, true), (8, false), (11, true), (17, true), (32, false)], checks);
+        assert_eq!(3, count_fresh(input.as_bytes())?);
+
+        assert!(count_fresh("3-5\n\n4\nfour\n".as_bytes()).is_err());
+        Ok(())
+    }
+}
@@ -1,4 +1,4 @@
-use day_06::part1::process;
+use day_06::{explain::explain, part1::process};
 use miette::Context;
 
 #[tracing::instrument]
@@ -6,7 +6,17 @@ fn main() -> miette::Result<()> {
     tracing_subscriber::fmt::init();
 
     let file = include_str!("../../input1.txt");
+
+    // `--explain` writes out every problem as an equation in both readings,
+    // `--json` does the same as json
+    let args: Vec<String> = std::env::args().collect();
+    if args.iter().any(|a| a == "--json") {
+        println!("{}", explain(file).context("explain part 1")?.to_json()?);
+    } else if args.iter().any(|a| a == "--explain") {
+        print!("{}", explain(file).context("explain part 1")?);
+    }
+
     let result = process(file).context("process part 1")?;
     println!("{}", result);
     Ok(())
-}
\ No newline at end of file
+}
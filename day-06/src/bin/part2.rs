@@ -1,4 +1,4 @@
-use day_06::part2::process;
+use day_06::{explain::explain, part2::process};
 use miette::Context;
 
 #[tracing::instrument]
@@ -6,6 +6,16 @@ fn main() -> miette::Result<()> {
     tracing_subscriber::fmt::init();
 
     let file = include_str!("../../input2.txt");
+
+    // `--explain` writes out every problem as an equation in both readings,
+    // `--json` does the same as json
+    let args: Vec<String> = std::env::args().collect();
+    if args.iter().any(|a| a == "--json") {
+        println!("{}", explain(file).context("explain part 2")?.to_json()?);
+    } else if args.iter().any(|a| a == "--explain") {
+        print!("{}", explain(file).context("explain part 2")?);
+    }
+
     let result = process(file).context("process part 2")?;
     println!("{}", result);
     Ok(())
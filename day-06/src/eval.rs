@@ -0,0 +1,238 @@
+use std::{collections::HashMap, fmt::Display, str::FromStr};
+
+use miette::{LabeledSpan, miette};
+use num_bigint::BigInt;
+
+use crate::worksheet::{Problem, Worksheet};
+
+/// What an operator does with the numbers of a problem, it is applied left
+/// to right so `a - b - c` is `(a - b) - c`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Add,
+    Sub,
+    Mul,
+    /// Only allowed when the division leaves no remainder.
+    Div,
+    Max,
+    Min,
+    Pow,
+}
+
+/// Maps the symbols found on the worksheet to their operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Registry {
+    ops: HashMap<String, Op>,
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        let ops = [
+            ("+", Op::Add),
+            ("-", Op::Sub),
+            ("*", Op::Mul),
+            ("/", Op::Div),
+            ("max", Op::Max),
+            ("min", Op::Min),
+            ("^", Op::Pow),
+        ];
+        Self { ops: ops.into_iter().map(|(s, op)| (s.to_string(), op)).collect() }
+    }
+}
+
+impl Registry {
+    pub fn register(&mut self, symbol: impl Into<String>, op: Op) {
+        self.ops.insert(symbol.into(), op);
+    }
+
+    pub fn get(&self, symbol: &str) -> Option<Op> {
+        self.ops.get(symbol).copied()
+    }
+}
+
+/// `Checked` works in `i64` and fails on overflow, `Arbitrary` never
+/// overflows but is a lot slower.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Precision {
+    Checked,
+    Arbitrary,
+}
+
+/// How the numbers of a problem are read, row by row for part 1 or column
+/// by column for part 2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reading {
+    Rows,
+    Columns,
+}
+
+/// Numbers the worksheet can be evaluated in, an `Err` is the reason the
+/// operation failed.
+pub trait Number: Sized + Clone + Ord + Display + FromStr<Err: Display> {
+    fn zero() -> Self;
+    fn add(&self, other: &Self) -> Result<Self, String>;
+    fn sub(&self, other: &Self) -> Result<Self, String>;
+    fn mul(&self, other: &Self) -> Result<Self, String>;
+    fn div(&self, other: &Self) -> Result<Self, String>;
+    fn pow(&self, other: &Self) -> Result<Self, String>;
+}
+
+impl Number for i64 {
+    fn zero() -> Self {
+        0
+    }
+
+    fn add(&self, other: &Self) -> Result<Self, String> {
+        self.checked_add(*other).ok_or_else(|| format!("{self} + {other} overflows i64"))
+    }
+
+    fn sub(&self, other: &Self) -> Result<Self, String> {
+        self.checked_sub(*other).ok_or_else(|| format!("{self} - {other} overflows i64"))
+    }
+
+    fn mul(&self, other: &Self) -> Result<Self, String> {
+        self.checked_mul(*other).ok_or_else(|| format!("{self} * {other} overflows i64"))
+    }
+
+    fn div(&self, other: &Self) -> Result<Self, String> {
+        match self.checked_rem(*other) {
+            None => Err(format!("{self} / {other} is not defined")),
+            Some(0) => Ok(self / other),
+            Some(_) => Err(format!("{self} is not divisible by {other}")),
+        }
+    }
+
+    fn pow(&self, other: &Self) -> Result<Self, String> {
+        let exp = u32::try_from(*other).map_err(|_| format!("can't raise {self} to the power {other}"))?;
+        self.checked_pow(exp).ok_or_else(|| format!("{self} ^ {other} overflows i64"))
+    }
+}
+
+impl Number for BigInt {
+    fn zero() -> Self {
+        BigInt::from(0)
+    }
+
+    fn add(&self, other: &Self) -> Result<Self, String> {
+        Ok(self + other)
+    }
+
+    fn sub(&self, other: &Self) -> Result<Self, String> {
+        Ok(self - other)
+    }
+
+    fn mul(&self, other: &Self) -> Result<Self, String> {
+        Ok(self * other)
+    }
+
+    fn div(&self, other: &Self) -> Result<Self, String> {
+        if *other == Self::zero() {
+            return Err(format!("{self} / {other} is not defined"));
+        }
+        if self % other != Self::zero() {
+            return Err(format!("{self} is not divisible by {other}"));
+        }
+        Ok(self / other)
+    }
+
+    fn pow(&self, other: &Self) -> Result<Self, String> {
+        let exp = u32::try_from(other).map_err(|_| format!("can't raise {self} to the power {other}"))?;
+        Ok(BigInt::pow(self, exp))
+    }
+}
+
+impl Op {
+    pub fn apply<N: Number>(self, a: &N, b: &N) -> Result<N, String> {
+        match self {
+            Op::Add => a.add(b),
+            Op::Sub => a.sub(b),
+            Op::Mul => a.mul(b),
+            Op::Div => a.div(b),
+            Op::Max => Ok(a.max(b).clone()),
+            Op::Min => Ok(a.min(b).clone()),
+            Op::Pow => a.pow(b),
+        }
+    }
+}
+
+impl Worksheet {
+    /// Grand total of all problems, written out in full.
+    pub fn evaluate(&self, registry: &Registry, reading: Reading, precision: Precision) -> miette::Result<String> {
+        match precision {
+            Precision::Checked => Ok(self.total::<i64>(registry, reading)?.to_string()),
+            Precision::Arbitrary => Ok(self.total::<BigInt>(registry, reading)?.to_string()),
+        }
+    }
+
+    pub fn total<N: Number>(&self, registry: &Registry, reading: Reading) -> miette::Result<N> {
+        let mut total = N::zero();
+        for problem in &self.problems {
+            let answer = self.answer(problem, registry, reading)?;
+            total = total.add(&answer).map_err(|reason| miette!("grand total: {}", reason))?;
+        }
+        Ok(total)
+    }
+
+    /// The answer to one problem, failures are labelled with the operator
+    /// of the problem on the worksheet.
+    pub fn answer<N: Number>(&self, problem: &Problem, registry: &Registry, reading: Reading) -> miette::Result<N> {
+        let fail = |reason: String| {
+            miette!(
+                labels = vec![LabeledSpan::at(problem.operator_at.clone(), reason.clone())],
+                "problem in columns {}..{}: {}",
+                problem.span.start,
+                problem.span.end,
+                reason
+            )
+            .with_source_code(self.source.clone())
+        };
+
+        let op = registry
+            .get(&problem.operator)
+            .ok_or_else(|| fail(format!("unknown operator {:?}", problem.operator)))?;
+        let numbers: Vec<N> = match reading {
+            Reading::Rows => problem.rows()?,
+            Reading::Columns => problem.columns()?,
+        };
+
+        let (first, rest) = numbers.split_first().ok_or_else(|| fail("problem has no numbers".to_string()))?;
+        rest.iter().try_fold(first.clone(), |acc, n| op.apply(&acc, n)).map_err(fail)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn evaluate(input: &str, reading: Reading, precision: Precision) -> miette::Result<String> {
+        Worksheet::parse(input)?.evaluate(&Registry::default(), reading, precision)
+    }
+
+    #[test]
+    fn test_operators() -> miette::Result<()> {
+        let input = "100 20 9 3 50\n  5  4 7 4 60\n  -  / ^ ^ min\n";
+        // 95 + 5 + 9^7 + 3^4 + 50
+        assert_eq!("4783200", evaluate(input, Reading::Rows, Precision::Checked)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = evaluate("7 2\n2 3\n/ %\n", Reading::Rows, Precision::Checked).unwrap_err();
+        assert!(error.to_string().contains("not divisible"), "{error}");
+        let labels: Vec<LabeledSpan> = error.labels().unwrap().collect();
+        assert_eq!(8, labels[0].offset());
+
+        let error = evaluate("7 2\n1 3\n/ %\n", Reading::Rows, Precision::Checked).unwrap_err();
+        assert!(error.to_string().contains("unknown operator \"%\""), "{error}");
+        assert_eq!(10, error.labels().unwrap().next().unwrap().offset());
+    }
+
+    #[test]
+    fn test_arbitrary_precision() -> miette::Result<()> {
+        let input = "9999999999\n9999999999\n*\n";
+        assert!(evaluate(input, Reading::Rows, Precision::Checked).is_err());
+        assert_eq!("99999999980000000001", evaluate(input, Reading::Rows, Precision::Arbitrary)?);
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+use std::{fmt, ops::Range};
+
+use miette::IntoDiagnostic;
+use num_bigint::BigInt;
+use serde::Serialize;
+
+use crate::{
+    eval::{Reading, Registry},
+    worksheet::{Problem, Worksheet},
+};
+
+/// Every problem on the worksheet worked out in both readings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Explanation {
+    pub problems: Vec<Worked>,
+}
+
+/// One problem, `span` is its column range on the worksheet.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Worked {
+    pub span: Range<usize>,
+    pub rows: Equation,
+    pub columns: Equation,
+}
+
+/// Numbers are kept as text so they survive the trip through json whatever
+/// their size.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Equation {
+    pub operator: String,
+    pub numbers: Vec<String>,
+    pub answer: String,
+}
+
+impl Explanation {
+    pub fn new(worksheet: &Worksheet, registry: &Registry) -> miette::Result<Self> {
+        let problems = worksheet
+            .problems
+            .iter()
+            .map(|problem| {
+                Ok(Worked {
+                    span: problem.span.clone(),
+                    rows: equation(worksheet, problem, registry, Reading::Rows)?,
+                    columns: equation(worksheet, problem, registry, Reading::Columns)?,
+                })
+            })
+            .collect::<miette::Result<_>>()?;
+        Ok(Self { problems })
+    }
+
+    pub fn to_json(&self) -> miette::Result<String> {
+        serde_json::to_string_pretty(self).into_diagnostic()
+    }
+}
+
+// worked out in big integers, an explanation shouldn't fail where the
+// answer would only overflow
+fn equation(worksheet: &Worksheet, problem: &Problem, registry: &Registry, reading: Reading) -> miette::Result<Equation> {
+    let numbers: Vec<BigInt> = match reading {
+        Reading::Rows => problem.rows()?,
+        Reading::Columns => problem.columns()?,
+    };
+    let answer: BigInt = worksheet.answer(problem, registry, reading)?;
+    Ok(Equation {
+        operator: problem.operator.clone(),
+        numbers: numbers.iter().map(BigInt::to_string).collect(),
+        answer: answer.to_string(),
+    })
+}
+
+pub fn explain(input: &str) -> miette::Result<Explanation> {
+    Explanation::new(&Worksheet::parse(input)?, &Registry::default())
+}
+
+impl fmt::Display for Equation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} = {}", self.numbers.join(&format!(" {} ", self.operator)), self.answer)
+    }
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for worked in &self.problems {
+            writeln!(f, "columns {}..{}", worked.span.start, worked.span.end)?;
+            writeln!(f, "  rows:    {}", worked.rows)?;
+            writeln!(f, "  columns: {}", worked.columns)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_explain() -> miette::Result<()> {
+        let input = "123 328  51 64 \n 45 64  387 23 \n  6 98  215 314\n*   +   *   + \n";
+        let explanation = explain(input)?;
+
+        let last = &explanation.problems[3];
+        assert_eq!(12..15, last.span);
+        assert_eq!("64 + 23 + 314 = 401", last.rows.to_string());
+        assert_eq!("4 + 431 + 623 = 1058", last.columns.to_string());
+        assert!(explanation.to_string().starts_with("columns 0..3\n  rows:    123 * 45 * 6 = 33210\n  columns: 356 * 24 * 1 = 8544\n"));
+
+        let json: serde_json::Value = serde_json::from_str(&explanation.to_json()?).into_diagnostic()?;
+        assert_eq!(json["problems"][3]["columns"]["answer"], "1058");
+        assert_eq!(json["problems"][1]["span"]["start"], 4);
+        Ok(())
+    }
+}
@@ -1,2 +1,5 @@
+pub mod eval;
+pub mod explain;
 pub mod part1;
 pub mod part2;
+pub mod worksheet;
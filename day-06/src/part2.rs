@@ -1,62 +1,17 @@
-use nom::{
-    character::complete::{line_ending, not_line_ending},
-    multi::many1,
-    sequence::terminated,
-    IResult, Parser,
+use crate::{
+    eval::{Precision, Reading, Registry},
+    worksheet::Worksheet,
 };
 
 #[tracing::instrument]
 pub fn process(input: &str) -> miette::Result<String> {
-    let (mut grid, ops) = parse_input(input);
-
-    grid = transpose(grid);
-    let cols = collapse(&grid);
-
-    let result: i64 = cols.iter().zip(ops.iter())
-        .map(|(col, op)| -> i64 {
-            match op {
-                '*' => col.iter().product(),
-                '+' => col.iter().sum(),
-                _ => panic!("unknown op: {op}"),
-            }
-        })
-        .sum();
-
-    Ok(result.to_string())
+    process_with(input, Precision::Checked)
 }
 
-fn transpose(grid: Vec<Vec<char>>) -> Vec<Vec<char>> {
-    (0..grid[0].len())
-        .map(|col| grid.iter().map(|row| row[col]).collect())
-        .collect()
-}
-
-fn collapse(grid: &[Vec<char>]) -> Vec<Vec<i64>> {
-    let nums: Vec<Option<i64>> = grid
-        .iter()
-        .map(|row| {
-            let s: String = row.iter().filter(|&&c| c != ' ').collect();
-            if s.is_empty() { None } else { Some(s.parse().unwrap()) }
-        })
-        .collect();
-
-    nums.split(|n| n.is_none())
-        .map(|chunk| chunk.iter().filter_map(|&n| n).collect())
-        .collect()
+pub fn process_with(input: &str, precision: Precision) -> miette::Result<String> {
+    Worksheet::parse(input)?.evaluate(&Registry::default(), Reading::Columns, precision)
 }
 
-fn parse_input(input: &str) -> (Vec<Vec<char>>, Vec<char>) {
-    let (_, mut lines) = many1(chars).parse(input).unwrap();
-    let ops: Vec<char> = lines.pop().unwrap().into_iter().filter(|&c| c != ' ').collect();
-    (lines, ops)
-}
-
-fn chars(input: &str) -> IResult<&str, Vec<char>> {
-    let (remainder, line) = terminated(not_line_ending, line_ending).parse(input)?;
-    Ok((remainder, line.chars().collect()))
-}
-
-
 #[cfg(test)]
 mod tests {
     use super::*;
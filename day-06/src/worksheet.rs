@@ -0,0 +1,147 @@
+use std::{fmt::Display, ops::Range, str::FromStr};
+
+use miette::miette;
+
+/// The math worksheet: problems sit side by side, separated by columns that
+/// are blank on every line, with the operator on the last line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Worksheet {
+    pub problems: Vec<Problem>,
+    pub source: String,
+}
+
+/// One problem, `span` is its column range on the worksheet and `cells` the
+/// text of every number line within that span, padded with spaces to the
+/// width of the span.
+/// `operator_at` is the byte range of the operator in the source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    pub span: Range<usize>,
+    pub operator: String,
+    pub operator_at: Range<usize>,
+    pub cells: Vec<String>,
+}
+
+impl Worksheet {
+    pub fn parse(input: &str) -> miette::Result<Self> {
+        let mut offsets = Vec::new();
+        let mut lines: Vec<Vec<char>> = Vec::new();
+        let mut offset = 0;
+        for line in input.split_inclusive('\n') {
+            let text = line.trim_end_matches(['\n', '\r']);
+            if !text.trim().is_empty() {
+                offsets.push(offset);
+                lines.push(text.chars().collect());
+            }
+            offset += line.len();
+        }
+        let operators = lines.pop().ok_or_else(|| miette!("empty worksheet"))?;
+        let operators_offset = offsets.pop().unwrap_or(0);
+        // byte offset of every column on the operator line
+        let column_offset = |col: usize| {
+            operators_offset + operators.iter().take(col).map(|c| c.len_utf8()).sum::<usize>()
+        };
+        if lines.is_empty() {
+            return Err(miette!("worksheet has operators but no numbers"));
+        }
+
+        // editors like to trim trailing spaces, so pad everything to the widest line
+        let width = lines.iter().chain([&operators]).map(Vec::len).max().unwrap_or(0);
+        let cell = |line: &[char], col: usize| line.get(col).copied().unwrap_or(' ');
+        let blank = |col: usize| lines.iter().chain([&operators]).all(|l| cell(l, col) == ' ');
+
+        let mut problems = Vec::new();
+        let mut start = None;
+        for col in 0..=width {
+            match (start, col == width || blank(col)) {
+                (None, false) => start = Some(col),
+                (Some(s), true) => {
+                    let span = s..col;
+                    let text: String = span.clone().map(|c| cell(&operators, c)).collect();
+                    let operator = text.trim().to_string();
+                    if operator.is_empty() {
+                        return Err(miette!("no operator under columns {}..{}", span.start, span.end));
+                    }
+                    let first = span.start + text.chars().take_while(|c| c.is_whitespace()).count();
+                    let last = (first + operator.chars().count()).min(operators.len());
+                    let operator_at = column_offset(first)..column_offset(last);
+                    let cells = lines
+                        .iter()
+                        .map(|l| span.clone().map(|c| cell(l, c)).collect())
+                        .collect();
+                    problems.push(Problem { span, operator, operator_at, cells });
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+
+        Ok(Self { problems, source: input.to_string() })
+    }
+}
+
+impl Problem {
+    /// The numbers read left to right along each line, as in part 1.
+    pub fn rows<N: FromStr>(&self) -> miette::Result<Vec<N>>
+    where
+        N::Err: Display,
+    {
+        self.cells
+            .iter()
+            .filter(|cell| !cell.trim().is_empty())
+            .map(|cell| {
+                cell.trim()
+                    .parse()
+                    .map_err(|e| miette!("bad number {:?} in columns {}..{}: {}", cell.trim(), self.span.start, self.span.end, e))
+            })
+            .collect()
+    }
+
+    /// The numbers read top to bottom in each column, starting from the
+    /// rightmost column, as in part 2.
+    pub fn columns<N: FromStr>(&self) -> miette::Result<Vec<N>>
+    where
+        N::Err: Display,
+    {
+        let width = self.span.len();
+        (0..width)
+            .rev()
+            .filter_map(|i| {
+                let digits: String = self
+                    .cells
+                    .iter()
+                    .filter_map(|cell| cell.chars().nth(i))
+                    .filter(|c| !c.is_whitespace())
+                    .collect();
+                (!digits.is_empty()).then(|| {
+                    digits
+                        .parse()
+                        .map_err(|e| miette!("bad number {:?} in column {}: {}", digits, self.span.start + i, e))
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ragged_lines() -> miette::Result<()> {
+        // same worksheet as the puzzle, with trailing spaces trimmed
+        let input = "123 328  51 64\n 45 64  387 23\n  6 98  215 314\n*   +   *   +";
+        let worksheet = Worksheet::parse(input)?;
+
+        let spans: Vec<Range<usize>> = worksheet.problems.iter().map(|p| p.span.clone()).collect();
+        assert_eq!(vec![0..3, 4..7, 8..11, 12..15], spans);
+        assert_eq!(vec!["*", "+", "*", "+"], worksheet.problems.iter().map(|p| p.operator.as_str()).collect::<Vec<_>>());
+        assert_eq!(58..59, worksheet.problems[3].operator_at);
+
+        let last = &worksheet.problems[3];
+        assert_eq!(vec!["64 ", "23 ", "314"], last.cells);
+        assert_eq!(vec![64, 23, 314], last.rows::<i64>()?);
+        assert_eq!(vec![4, 431, 623], last.columns::<i64>()?);
+        Ok(())
+    }
+}
@@ -6,7 +6,23 @@ fn main() -> miette::Result<()> {
     tracing_subscriber::fmt::init();
 
     let file = include_str!("../../input1.txt");
+
+    // with the `record` feature, `--play [fps]`, `--gif <file>` and
+    // `--png <dir>` show the beam working its way down
+    #[cfg(feature = "record")]
+    {
+        use day_07::record::record_beams;
+        use frame_recorder::{Output, Recorder};
+
+        let output = Output::from_args(std::env::args())?;
+        if output.is_requested() {
+            let mut recorder = Recorder::new();
+            record_beams(file, &day_07::manifold::Manifold::parse(file)?, &mut recorder)?;
+            output.write(&recorder).context("record part 1")?;
+        }
+    }
+
     let result = process(file).context("process part 1")?;
     println!("{}", result);
     Ok(())
-}
\ No newline at end of file
+}
@@ -1,2 +1,5 @@
+pub mod manifold;
 pub mod part1;
 pub mod part2;
+#[cfg(feature = "record")]
+pub mod record;
@@ -0,0 +1,516 @@
+use std::collections::{HashMap, HashSet};
+
+use glam::IVec2;
+use miette::miette;
+
+/// What can sit in a cell of the manifold. Anything the parser doesn't know
+/// is empty space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cell {
+    Empty,
+    Source,
+    /// Splits the beam sideways into `fan_out` beams that carry on in the
+    /// same direction.
+    Splitter,
+    /// `/`, turns a beam going down to the left.
+    Mirror,
+    /// `\`, turns a beam going down to the right.
+    BackMirror,
+    /// `#`, the beam stops here.
+    Absorber,
+    /// `v`, sends the beam down whichever way it came in.
+    Down,
+    /// `>`, sends the beam right whichever way it came in.
+    Right,
+}
+
+/// The tachyon manifold as a dense grid, `sources` are the column and row of
+/// every `S` a beam starts from, top to bottom.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Manifold {
+    pub width: usize,
+    pub height: usize,
+    pub sources: Vec<(usize, usize)>,
+    /// Number of beams coming out of a splitter, 2 in the puzzle.
+    pub fan_out: usize,
+    cells: Vec<Cell>,
+}
+
+/// Where the timelines went, per source and per splitter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Analysis {
+    pub sources: Vec<Source>,
+    /// Every splitter on the manifold, top to bottom, reached or not.
+    pub splitters: Vec<Splitter>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Source {
+    pub pos: (usize, usize),
+    pub timelines: u128,
+}
+
+/// `hits` is the number of timelines that run into the splitter, `timelines`
+/// the number of timelines at the end that went through it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Splitter {
+    pub pos: (usize, usize),
+    pub hits: u128,
+    pub timelines: u128,
+}
+
+impl Analysis {
+    pub fn unreached(&self) -> impl Iterator<Item = &Splitter> {
+        self.splitters.iter().filter(|s| s.hits == 0)
+    }
+}
+
+/// What one run through the manifold found: the number of splitters the
+/// beams hit and the number of timelines that came to an end, summed over
+/// all sources.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sweep {
+    pub splits: u64,
+    pub timelines: u128,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Beam {
+    pos: IVec2,
+    dir: IVec2,
+}
+
+// where a beam goes after its next step, `End` finishes its timeline
+enum Step {
+    End,
+    Beams(Vec<Beam>),
+}
+
+impl Manifold {
+    pub fn parse(input: &str) -> miette::Result<Self> {
+        let lines: Vec<&[u8]> = input.lines().map(str::as_bytes).collect();
+        let width = lines.iter().map(|l| l.len()).max().unwrap_or(0);
+        let height = lines.len();
+
+        let mut cells = vec![Cell::Empty; width * height];
+        let mut sources = Vec::new();
+        for (y, line) in lines.iter().enumerate() {
+            for (x, &c) in line.iter().enumerate() {
+                cells[y * width + x] = match c {
+                    b'S' => {
+                        sources.push((x, y));
+                        Cell::Source
+                    }
+                    b'^' => Cell::Splitter,
+                    b'/' => Cell::Mirror,
+                    b'\\' => Cell::BackMirror,
+                    b'#' => Cell::Absorber,
+                    b'v' => Cell::Down,
+                    b'>' => Cell::Right,
+                    _ => Cell::Empty,
+                };
+            }
+        }
+        if sources.is_empty() {
+            return Err(miette!("no S in the manifold"));
+        }
+
+        Ok(Self { width, height, sources, fan_out: 2, cells })
+    }
+
+    pub fn with_fan_out(mut self, fan_out: usize) -> Self {
+        self.fan_out = fan_out;
+        self
+    }
+
+    pub fn cell(&self, x: usize, y: usize) -> Option<Cell> {
+        (x < self.width && y < self.height).then(|| self.cells[y * self.width + x])
+    }
+
+    pub fn is_splitter(&self, x: usize, y: usize) -> bool {
+        self.cell(x, y) == Some(Cell::Splitter)
+    }
+
+    /// True when there's nothing but splitters, so every beam can only go
+    /// down and a sweep gives the right answer.
+    pub fn is_downwards(&self) -> bool {
+        self.cells.iter().all(|c| matches!(c, Cell::Empty | Cell::Source | Cell::Splitter))
+    }
+
+    /// Sweep when every beam can only go down, trace otherwise.
+    pub fn run(&self) -> miette::Result<Sweep> {
+        if self.is_downwards() { self.sweep() } else { self.trace() }
+    }
+
+    pub fn sweep(&self) -> miette::Result<Sweep> {
+        self.sweep_with(|_, _| {})
+    }
+
+    /// Move the beams down one row at a time, keeping the number of
+    /// timelines in every column. Hands over the row number and the counts
+    /// after every row. Only splitters are looked at, use
+    /// [`Manifold::trace`] for the other cells.
+    pub fn sweep_with(&self, mut on_row: impl FnMut(usize, &[u128])) -> miette::Result<Sweep> {
+        let top = self.sources[0].1;
+        let mut row = vec![0u128; self.width];
+        let mut next = vec![0u128; self.width];
+        let mut sources = self.sources.iter().peekable();
+        while let Some(&(sx, _)) = sources.next_if(|s| s.1 == top) {
+            row[sx] += 1;
+        }
+
+        // beams split off the sides never meet a splitter again, so they
+        // only have to be counted
+        let mut escaped = 0u128;
+        let mut splits = 0;
+        let offsets = self.offsets();
+        let overflow = |y: usize| miette!("timelines overflow u128 on row {}", y);
+
+        for y in top + 1..self.height {
+            next.fill(0);
+            for x in 0..self.width {
+                let count = row[x];
+                if count == 0 {
+                    continue;
+                }
+                if !self.is_splitter(x, y) {
+                    next[x] += count;
+                    continue;
+                }
+                splits += 1;
+                for offset in &offsets {
+                    let target = match x.checked_add_signed(*offset as isize).filter(|&nx| nx < self.width) {
+                        Some(nx) => &mut next[nx],
+                        None => &mut escaped,
+                    };
+                    *target = target.checked_add(count).ok_or_else(|| overflow(y))?;
+                }
+            }
+            // sources further down join in once the sweep gets to their row
+            while let Some(&(sx, _)) = sources.next_if(|s| s.1 == y) {
+                next[sx] += 1;
+            }
+            std::mem::swap(&mut row, &mut next);
+            on_row(y, &row);
+        }
+
+        let timelines = row
+            .iter()
+            .try_fold(escaped, |acc, &n| acc.checked_add(n))
+            .ok_or_else(|| overflow(self.height))?;
+        Ok(Sweep { splits, timelines })
+    }
+
+    /// Follow beams in any direction, counting every way a beam can come to
+    /// an end, by leaving the manifold or in an absorber. A beam that can
+    /// come back to where it was, going the same way, would make endless
+    /// timelines and is reported as an error.
+    pub fn trace(&self) -> miette::Result<Sweep> {
+        let analysis = self.analyse()?;
+        let timelines = analysis
+            .sources
+            .iter()
+            .try_fold(0u128, |acc, s| acc.checked_add(s.timelines))
+            .ok_or_else(|| miette!("timelines overflow u128"))?;
+        let splits = analysis.splitters.len() - analysis.unreached().count();
+        Ok(Sweep { splits: splits as u64, timelines })
+    }
+
+    /// Trace the beams and work out how many timelines every source starts
+    /// and every splitter sees.
+    pub fn analyse(&self) -> miette::Result<Analysis> {
+        let starts: Vec<Beam> = self
+            .sources
+            .iter()
+            .map(|&(x, y)| Beam { pos: IVec2::new(x as i32, y as i32), dir: IVec2::Y })
+            .collect();
+        let (order, timelines) = self.explore(&starts)?;
+
+        // timelines arriving at every beam, handed down in topological order
+        let mut arriving: HashMap<Beam, u128> = starts.iter().map(|&b| (b, 1)).collect();
+        let mut splitters: HashMap<IVec2, (u128, u128)> = HashMap::new();
+        let add = |a: u128, b: u128| a.checked_add(b).ok_or_else(|| miette!("timelines overflow u128"));
+        for beam in order.iter().rev() {
+            let Step::Beams(next) = self.step(*beam) else { continue };
+            let count = arriving.get(beam).copied().unwrap_or(0);
+            for b in next {
+                let entry = arriving.entry(b).or_default();
+                *entry = add(*entry, count)?;
+            }
+            let pos = beam.pos + beam.dir;
+            if self.cell_at(pos) == Some(Cell::Splitter) {
+                let through = count.checked_mul(timelines[beam]).ok_or_else(|| miette!("timelines overflow u128"))?;
+                let (hits, total) = splitters.entry(pos).or_default();
+                *hits = add(*hits, count)?;
+                *total = add(*total, through)?;
+            }
+        }
+
+        let sources = self
+            .sources
+            .iter()
+            .zip(&starts)
+            .map(|(&pos, start)| Source { pos, timelines: timelines[start] })
+            .collect();
+        let splitters = (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+            .filter(|&(x, y)| self.is_splitter(x, y))
+            .map(|(x, y)| {
+                let (hits, timelines) = splitters.get(&IVec2::new(x as i32, y as i32)).copied().unwrap_or_default();
+                Splitter { pos: (x, y), hits, timelines }
+            })
+            .collect();
+        Ok(Analysis { sources, splitters })
+    }
+
+    // every beam reachable from the starts in depth first post order, with
+    // the number of timelines each one leads to
+    fn explore(&self, starts: &[Beam]) -> miette::Result<(Vec<Beam>, HashMap<Beam, u128>)> {
+        let mut timelines: HashMap<Beam, u128> = HashMap::new();
+        let mut open: HashSet<Beam> = HashSet::new();
+        let mut order = Vec::new();
+
+        // without recursion, a beam is visited once to push where it goes
+        // and once more to add those up
+        let mut stack: Vec<(Beam, bool)> = starts.iter().rev().map(|&b| (b, false)).collect();
+        while let Some((beam, done)) = stack.pop() {
+            let step = self.step(beam);
+            if done {
+                let count = match step {
+                    Step::End => 1,
+                    Step::Beams(next) => next
+                        .iter()
+                        .try_fold(0u128, |acc, b| acc.checked_add(timelines[b]))
+                        .ok_or_else(|| miette!("timelines overflow u128 at ({}, {})", beam.pos.x, beam.pos.y))?,
+                };
+                timelines.insert(beam, count);
+                open.remove(&beam);
+                order.push(beam);
+                continue;
+            }
+
+            if timelines.contains_key(&beam) {
+                continue;
+            }
+            if !open.insert(beam) {
+                return Err(miette!(
+                    "beam loops forever, it comes back to ({}, {}) heading {}",
+                    beam.pos.x,
+                    beam.pos.y,
+                    heading(beam.dir)
+                ));
+            }
+            stack.push((beam, true));
+            if let Step::Beams(next) = step {
+                stack.extend(next.into_iter().filter(|b| !timelines.contains_key(b)).map(|b| (b, false)));
+            }
+        }
+
+        Ok((order, timelines))
+    }
+
+    fn step(&self, beam: Beam) -> Step {
+        // beams out of a splitter can land off the side or on an absorber
+        if matches!(self.cell_at(beam.pos), None | Some(Cell::Absorber)) {
+            return Step::End;
+        }
+        let pos = beam.pos + beam.dir;
+        match self.cell_at(pos) {
+            None | Some(Cell::Absorber) => Step::End,
+            Some(Cell::Splitter) => {
+                let side = beam.dir.perp();
+                Step::Beams(
+                    self.offsets()
+                        .into_iter()
+                        .map(|offset| self.land(pos + side * offset, beam.dir))
+                        .collect(),
+                )
+            }
+            Some(_) => Step::Beams(vec![self.land(pos, beam.dir)]),
+        }
+    }
+
+    // a beam moving onto `pos`, turned by whatever is there. It goes
+    // straight over a splitter, those only split beams that run into them
+    fn land(&self, pos: IVec2, dir: IVec2) -> Beam {
+        let dir = match self.cell_at(pos) {
+            Some(Cell::Mirror) => IVec2::new(-dir.y, -dir.x),
+            Some(Cell::BackMirror) => IVec2::new(dir.y, dir.x),
+            Some(Cell::Down) => IVec2::Y,
+            Some(Cell::Right) => IVec2::X,
+            _ => dir,
+        };
+        Beam { pos, dir }
+    }
+
+    fn cell_at(&self, pos: IVec2) -> Option<Cell> {
+        (pos.x >= 0 && pos.y >= 0)
+            .then(|| self.cell(pos.x as usize, pos.y as usize))
+            .flatten()
+    }
+
+    // sideways offsets of the beams out of a splitter, an odd fan-out also
+    // lets one straight through
+    fn offsets(&self) -> Vec<i32> {
+        let half = (self.fan_out / 2) as i32;
+        let mut offsets: Vec<i32> = (1..=half).flat_map(|i| [-i, i]).collect();
+        if self.fan_out % 2 == 1 {
+            offsets.push(0);
+        }
+        offsets
+    }
+}
+
+fn heading(dir: IVec2) -> &'static str {
+    match (dir.x, dir.y) {
+        (0, 1) => "down",
+        (0, -1) => "up",
+        (1, 0) => "right",
+        _ => "left",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[test]
+    fn test_sweep() -> miette::Result<()> {
+        let input = ".......S.......
+...............
+.......^.......
+...............
+......^.^......
+...............
+.....^.^.^.....
+...............
+....^.^...^....
+...............
+...^.^...^.^...
+...............
+..^...^.....^..
+...............
+.^.^.^.^.^...^.
+...............
+";
+        let manifold = Manifold::parse(input)?;
+        assert_eq!(Sweep { splits: 21, timelines: 40 }, manifold.sweep()?);
+        assert_eq!(manifold.sweep()?, manifold.trace()?);
+
+        let manifold = manifold.with_fan_out(3);
+        assert_eq!(manifold.sweep()?, manifold.trace()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_edges_and_overflow() -> miette::Result<()> {
+        // half of the split leaves the grid but still counts as a timeline
+        assert_eq!(Sweep { splits: 1, timelines: 2 }, Manifold::parse("S.\n..\n^.\n")?.sweep()?);
+
+        // every pair of rows doubles the timelines in the middle column
+        let doubling = |pairs: usize| format!(".S.\n{}", ".^.\n^.^\n".repeat(pairs));
+        assert_eq!(3 * (1 << 10) - 2, Manifold::parse(&doubling(10))?.sweep()?.timelines);
+        assert!(Manifold::parse(&doubling(130))?.sweep().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_trace() -> miette::Result<()> {
+        // the beam bounces right and gets split up and down, one half runs
+        // into the absorber and the other is sent back down
+        let input = "S....
+....#
+\\.^..
+...v.
+.....
+";
+        assert_eq!(Sweep { splits: 1, timelines: 2 }, Manifold::parse(input)?.trace()?);
+
+        // three ways out of the splitter, the middle one runs into the redirector
+        let input = "..S..
+.....
+..^..
+..>..
+";
+        assert_eq!(Sweep { splits: 1, timelines: 3 }, Manifold::parse(input)?.with_fan_out(3).run()?);
+        Ok(())
+    }
+
+    #[rstest]
+    // the left beam ends in the absorber, before the splitter below
+    #[case("..S..\n.....\n.#^..\n.....\n.^...\n.....\n", Sweep { splits: 1, timelines: 2 })]
+    // the mirror sends the left beam off the side
+    #[case("..S..\n.....\n./^..\n.....\n.^...\n.....\n", Sweep { splits: 1, timelines: 2 })]
+    // the back mirror and the redirector send the left beam back into the
+    // side of the splitter, where it's split up and down
+    #[case("..S..\n.....\n.\\^..\n.....\n.^...\n.....\n", Sweep { splits: 1, timelines: 3 })]
+    #[case("..S..\n.....\n.>^..\n.....\n.^...\n.....\n", Sweep { splits: 1, timelines: 3 })]
+    // sent down, the left beam gets to the splitter below
+    #[case("..S..\n.....\n.v^..\n.....\n.^...\n.....\n", Sweep { splits: 2, timelines: 3 })]
+    fn test_trace_beside_splitter(#[case] input: &str, #[case] expected: Sweep) -> miette::Result<()> {
+        assert_eq!(expected, Manifold::parse(input)?.trace()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_cycle() -> miette::Result<()> {
+        // round and round between the redirector and the mirrors
+        let input = ".S..
+.>.\\
+....
+.\\./
+";
+        let error = Manifold::parse(input)?.trace().unwrap_err();
+        assert!(error.to_string().contains("loops forever"), "{error}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_sources() -> miette::Result<()> {
+        // the second source starts lower down and joins the first beam
+        let input = ".S...
+.....
+.^S..
+.....
+..^..
+.....
+";
+        let manifold = Manifold::parse(input)?;
+        assert_eq!(Sweep { splits: 2, timelines: 5 }, manifold.sweep()?);
+        assert_eq!(manifold.sweep()?, manifold.trace()?);
+
+        let analysis = manifold.analyse()?;
+        let per_source: Vec<u128> = analysis.sources.iter().map(|s| s.timelines).collect();
+        assert_eq!(vec![3, 2], per_source);
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyse() -> miette::Result<()> {
+        let input = ".......S.......
+...............
+.......^.......
+...............
+......^.^......
+...............
+.....^.^.^.....
+...............
+....^.^...^....
+...............
+...^.^...^.^...
+...............
+..^...^.....^..
+...............
+.^.^.^.^.^...^.
+...............
+";
+        let analysis = Manifold::parse(input)?.analyse()?;
+        assert_eq!(Splitter { pos: (7, 2), hits: 1, timelines: 40 }, analysis.splitters[0]);
+        // the second row splits the timelines between its two splitters
+        assert_eq!(40, analysis.splitters[1].timelines + analysis.splitters[2].timelines);
+        assert_eq!(vec![(9, 14)], analysis.unreached().map(|s| s.pos).collect::<Vec<_>>());
+        assert_eq!(40, analysis.sources[0].timelines);
+        Ok(())
+    }
+}
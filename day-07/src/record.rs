@@ -0,0 +1,68 @@
+use frame_recorder::{Frame, Recorder};
+use miette::miette;
+
+use crate::manifold::{Cell, Manifold, Sweep};
+
+/// Sweep the manifold and record a frame for every row the beams move down.
+/// Only splitters can be drawn this way, so anything else is an error rather
+/// than a recording that leaves it out.
+pub fn record_beams(input: &str, manifold: &Manifold, recorder: &mut Recorder) -> miette::Result<Sweep> {
+    if !manifold.is_downwards() {
+        return Err(miette!("only manifolds with nothing but splitters can be recorded"));
+    }
+
+    let mut rows: Vec<(usize, Vec<usize>)> = Vec::new();
+    let sweep = manifold.sweep_with(|y, row| {
+        // draw beams over empty space only, so splitters and sources stay visible
+        let beams = (0..row.len()).filter(|&x| row[x] > 0 && manifold.cell(x, y) == Some(Cell::Empty));
+        rows.push((y, beams.collect()));
+    })?;
+
+    recorder.push(Frame::from_text(input, '.'));
+    for (y, beams) in rows {
+        recorder.push_diff(beams.into_iter().map(|x| (x, y, '|')))?;
+    }
+    Ok(sweep)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_beams() -> miette::Result<()> {
+        let input = "..S..
+.....
+..^..
+.....
+";
+        let mut recorder = Recorder::new();
+        let sweep = record_beams(input, &Manifold::parse(input)?, &mut recorder)?;
+        assert_eq!(1, sweep.splits);
+        assert_eq!("..S..\n..|..\n.|^|.\n.|.|.\n", recorder.frames().last().unwrap().to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn test_record_beams_fan_out() -> miette::Result<()> {
+        let input = "..S..
+.....
+..^..
+.S...
+";
+        let manifold = Manifold::parse(input)?.with_fan_out(3);
+        let mut recorder = Recorder::new();
+        let sweep = record_beams(input, &manifold, &mut recorder)?;
+        assert_eq!(1, sweep.splits);
+        assert_eq!("..S..\n..|..\n.|^|.\n.S||.\n", recorder.frames().last().unwrap().to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn test_record_beams_mirror() -> miette::Result<()> {
+        let input = "..S..\n..\\..\n";
+        let mut recorder = Recorder::new();
+        assert!(record_beams(input, &Manifold::parse(input)?, &mut recorder).is_err());
+        Ok(())
+    }
+}
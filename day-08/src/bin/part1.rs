@@ -1,4 +1,9 @@
-use day_08::part1::process;
+use day_08::{
+    circuits::{Metric, parse_boxes},
+    export::Graph,
+    part1::process,
+    timeline::Timeline,
+};
 use miette::Context;
 
 #[tracing::instrument]
@@ -6,7 +11,25 @@ fn main() -> miette::Result<()> {
     tracing_subscriber::fmt::init();
 
     let file = include_str!("../../input1.txt");
+
+    // `--csv` prints the circuits after every connection, up to the one
+    // that joins everything, `--dot` and `--json` the first 1000 connections
+    let args: Vec<String> = std::env::args().collect();
+    if args.iter().any(|a| a == "--csv") {
+        let boxes = parse_boxes(file).context("timeline part 1")?;
+        print!("{}", Timeline::new(&boxes, Metric::Euclidean).to_csv());
+    }
+    if args.iter().any(|a| a == "--dot" || a == "--json") {
+        let boxes = parse_boxes(file).context("export part 1")?;
+        let graph = Graph::first(&boxes, Metric::Euclidean, 1000);
+        if args.iter().any(|a| a == "--json") {
+            println!("{}", graph.to_json()?);
+        } else {
+            print!("{}", graph.to_dot());
+        }
+    }
+
     let result = process(file).context("process part 1")?;
     println!("{}", result);
     Ok(())
-}
\ No newline at end of file
+}
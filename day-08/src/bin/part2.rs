@@ -1,4 +1,8 @@
-use day_08::part2::process;
+use day_08::{
+    circuits::{Metric, parse_boxes},
+    export::Graph,
+    part2::process,
+};
 use miette::Context;
 
 #[tracing::instrument]
@@ -6,6 +10,18 @@ fn main() -> miette::Result<()> {
     tracing_subscriber::fmt::init();
 
     let file = include_str!("../../input2.txt");
+
+    // `--dot` and `--json` export the spanning tree that joins every box
+    let args: Vec<String> = std::env::args().collect();
+    if args.iter().any(|a| a == "--dot" || a == "--json") {
+        let graph = Graph::spanning_tree(&parse_boxes(file).context("export part 2")?, Metric::Euclidean);
+        if args.iter().any(|a| a == "--json") {
+            println!("{}", graph.to_json()?);
+        } else {
+            print!("{}", graph.to_dot());
+        }
+    }
+
     let result = process(file).context("process part 2")?;
     println!("{}", result);
     Ok(())
@@ -0,0 +1,138 @@
+use std::ops::Index;
+
+use itertools::Itertools;
+use miette::miette;
+use nom::{
+    IResult, Parser,
+    character::complete::{char, i64, line_ending},
+    multi::separated_list1,
+};
+
+/// The junction boxes, all with the same number of coordinates, stored one
+/// after the other. `boxes[i]` is the coordinates of box `i`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Boxes {
+    pub dimensions: usize,
+    coords: Vec<i64>,
+}
+
+impl Boxes {
+    pub fn new(dimensions: usize, points: impl IntoIterator<Item = Vec<i64>>) -> miette::Result<Self> {
+        let mut coords = Vec::new();
+        for (i, point) in points.into_iter().enumerate() {
+            if point.len() != dimensions {
+                return Err(miette!("box {} has {} coordinates, expected {}", i + 1, point.len(), dimensions));
+            }
+            coords.extend(point);
+        }
+        Ok(Self { dimensions, coords })
+    }
+
+    pub fn len(&self) -> usize {
+        self.coords.len() / self.dimensions.max(1)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.coords.is_empty()
+    }
+}
+
+impl Index<usize> for Boxes {
+    type Output = [i64];
+
+    fn index(&self, i: usize) -> &[i64] {
+        &self.coords[i * self.dimensions..(i + 1) * self.dimensions]
+    }
+}
+
+/// How far apart two boxes are. Euclidean distances are left squared, they
+/// sort the same and stay integers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Metric {
+    #[default]
+    Euclidean,
+    Manhattan,
+    Chebyshev,
+}
+
+impl Metric {
+    pub fn distance(self, a: &[i64], b: &[i64]) -> i64 {
+        let diffs = a.iter().zip(b).map(|(a, b)| a - b);
+        match self {
+            Metric::Euclidean => diffs.map(|d| d * d).sum(),
+            Metric::Manhattan => diffs.map(i64::abs).sum(),
+            Metric::Chebyshev => diffs.map(i64::abs).max().unwrap_or(0),
+        }
+    }
+
+    /// The least distance two boxes can be apart when they're `diff` apart
+    /// along one axis.
+    pub fn axis_distance(self, diff: i64) -> i64 {
+        match self {
+            Metric::Euclidean => diff * diff,
+            Metric::Manhattan | Metric::Chebyshev => diff.abs(),
+        }
+    }
+}
+
+/// Two junction boxes, by index into the input, and how far apart they are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pair {
+    pub a: usize,
+    pub b: usize,
+    pub distance: i64,
+}
+
+/// Every pair of boxes, in no particular order. Fine for the puzzle, but
+/// it's quadratic in memory, [`crate::kdtree::PairsByDistance`] isn't.
+pub fn all_pairs(boxes: &Boxes, metric: Metric) -> Vec<Pair> {
+    (0..boxes.len())
+        .tuple_combinations()
+        .map(|(a, b)| Pair { a, b, distance: metric.distance(&boxes[a], &boxes[b]) })
+        .collect()
+}
+
+/// Read one box per line, the number of commas on the first line decides
+/// whether they're 2D, 3D or 4D.
+pub fn parse_boxes(input: &str) -> miette::Result<Boxes> {
+    let dimensions = input.lines().next().map_or(0, |line| line.matches(',').count() + 1);
+    if !(2..=4).contains(&dimensions) {
+        return Err(miette!("expected 2 to 4 coordinates per box, found {}", dimensions));
+    }
+
+    let (rest, points) = read_input(input.trim_end()).map_err(|e| miette!("parse failed {}", e))?;
+    if !rest.is_empty() {
+        return Err(miette!("parse failed at {:?}", rest.lines().next().unwrap_or_default()));
+    }
+    Boxes::new(dimensions, points)
+}
+
+pub fn read_input(input: &str) -> IResult<&str, Vec<Vec<i64>>> {
+    separated_list1(line_ending, separated_list1(char(','), i64)).parse(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_boxes() -> miette::Result<()> {
+        let boxes = parse_boxes("1,2\n3,4\n")?;
+        assert_eq!((2, 2), (boxes.dimensions, boxes.len()));
+        assert_eq!(&[3, 4], &boxes[1]);
+
+        assert_eq!(4, parse_boxes("1,2,3,4\n5,6,7,8")?.dimensions);
+        let error = parse_boxes("1,2,3\n4,5\n").unwrap_err();
+        assert_eq!("box 2 has 2 coordinates, expected 3", error.to_string());
+        assert!(parse_boxes("1\n2\n").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_metrics() {
+        let (a, b) = ([1, 2, 3], [4, -2, 3]);
+        assert_eq!(25, Metric::Euclidean.distance(&a, &b));
+        assert_eq!(7, Metric::Manhattan.distance(&a, &b));
+        assert_eq!(4, Metric::Chebyshev.distance(&a, &b));
+    }
+}
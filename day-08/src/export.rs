@@ -0,0 +1,128 @@
+use std::{collections::HashMap, fmt::Write};
+
+use miette::IntoDiagnostic;
+use serde::Serialize;
+
+use crate::{
+    circuits::{Boxes, Metric},
+    dsu::Dsu,
+    kdtree::PairsByDistance,
+    timeline::Timeline,
+};
+
+/// The junction boxes and the connections that joined circuits, ready to be
+/// drawn.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Graph {
+    pub nodes: Vec<Node>,
+    pub edges: Vec<Edge>,
+}
+
+/// `circuit` numbers the circuits in the order their first box appears in
+/// the input.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Node {
+    pub id: usize,
+    pub coords: Vec<i64>,
+    pub circuit: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Edge {
+    pub a: usize,
+    pub b: usize,
+    pub distance: i64,
+}
+
+// colours for the first few circuits, the rest cycle through them again
+const COLOURS: [&str; 10] = [
+    "#e6194b", "#3cb44b", "#ffe119", "#4363d8", "#f58231", "#911eb4", "#46f0f0", "#f032e6", "#bcf60c", "#fabebe",
+];
+
+impl Graph {
+    /// The circuits once the `k` closest pairs are connected, as in part 1.
+    pub fn first(boxes: &Boxes, metric: Metric, k: usize) -> Self {
+        let mut circuits = Dsu::new(boxes.len());
+        let edges = PairsByDistance::new(boxes, metric)
+            .take(k)
+            .filter(|p| circuits.union(p.a, p.b))
+            .map(|p| Edge { a: p.a, b: p.b, distance: p.distance });
+        Self::new(boxes, edges.collect::<Vec<_>>())
+    }
+
+    /// Every connection up to the one that makes it all one circuit, as in
+    /// part 2, which is a minimum spanning tree.
+    pub fn spanning_tree(boxes: &Boxes, metric: Metric) -> Self {
+        let timeline = Timeline::new(boxes, metric);
+        Self::new(boxes, timeline.steps.iter().map(|s| Edge { a: s.a, b: s.b, distance: s.distance }))
+    }
+
+    fn new(boxes: &Boxes, edges: impl IntoIterator<Item = Edge>) -> Self {
+        let edges: Vec<Edge> = edges.into_iter().collect();
+        let mut circuits = Dsu::new(boxes.len());
+        for edge in &edges {
+            circuits.union(edge.a, edge.b);
+        }
+
+        let mut numbers: HashMap<usize, usize> = HashMap::new();
+        let nodes = (0..boxes.len())
+            .map(|id| {
+                let root = circuits.find(id);
+                let next = numbers.len();
+                let circuit = *numbers.entry(root).or_insert(next);
+                Node { id, coords: boxes[id].to_vec(), circuit }
+            })
+            .collect();
+        Self { nodes, edges }
+    }
+
+    pub fn to_json(&self) -> miette::Result<String> {
+        serde_json::to_string_pretty(self).into_diagnostic()
+    }
+
+    /// GraphViz source, boxes in a circuit of their own are left grey.
+    pub fn to_dot(&self) -> String {
+        let mut sizes: HashMap<usize, usize> = HashMap::new();
+        for node in &self.nodes {
+            *sizes.entry(node.circuit).or_default() += 1;
+        }
+
+        let mut dot = String::from("graph circuits {\n    node [style=filled];\n");
+        for node in &self.nodes {
+            let label = node.coords.iter().map(i64::to_string).collect::<Vec<_>>().join(",");
+            let colour = if sizes[&node.circuit] == 1 { "lightgrey" } else { COLOURS[node.circuit % COLOURS.len()] };
+            writeln!(dot, "    {} [label=\"{}\", fillcolor=\"{}\"];", node.id, label, colour).unwrap();
+        }
+        for edge in &self.edges {
+            writeln!(dot, "    {} -- {} [label=\"{}\"];", edge.a, edge.b, edge.distance).unwrap();
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuits::parse_boxes;
+
+    #[test]
+    fn test_export() -> miette::Result<()> {
+        let boxes = parse_boxes("0,0,0\n1,0,0\n10,0,0\n12,0,0\n30,0,0\n")?;
+
+        let graph = Graph::first(&boxes, Metric::Euclidean, 2);
+        assert_eq!(vec![0, 0, 1, 1, 2], graph.nodes.iter().map(|n| n.circuit).collect::<Vec<_>>());
+        let dot = graph.to_dot();
+        assert!(dot.contains("    0 [label=\"0,0,0\", fillcolor=\"#e6194b\"];\n"), "{dot}");
+        assert!(dot.contains("    4 [label=\"30,0,0\", fillcolor=\"lightgrey\"];\n"), "{dot}");
+        assert!(dot.contains("    2 -- 3 [label=\"4\"];\n"), "{dot}");
+
+        let tree = Graph::spanning_tree(&boxes, Metric::Euclidean);
+        assert_eq!(4, tree.edges.len());
+        let json: serde_json::Value = serde_json::from_str(&tree.to_json()?).into_diagnostic()?;
+        assert_eq!(json["edges"][3]["a"], 3);
+        assert_eq!(json["nodes"][4]["coords"][0], 30);
+        assert_eq!(json["nodes"][4]["circuit"], 0);
+        Ok(())
+    }
+}
@@ -0,0 +1,162 @@
+use std::{
+    cmp::Reverse,
+    collections::BinaryHeap,
+};
+
+use crate::circuits::{Boxes, Metric, Pair};
+
+/// A k-d tree over the junction boxes, kept as one array where the middle
+/// of every slice is the node splitting it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KdTree {
+    boxes: Boxes,
+    metric: Metric,
+    order: Vec<usize>,
+}
+
+impl KdTree {
+    pub fn new(boxes: &Boxes, metric: Metric) -> Self {
+        let mut order: Vec<usize> = (0..boxes.len()).collect();
+        build(boxes, &mut order, 0);
+        Self { boxes: boxes.clone(), metric, order }
+    }
+
+    /// The `k` boxes after `target` in input order that are closest to it,
+    /// closest first. Ties go to the lowest index, so asking for more never
+    /// reorders what was found before.
+    pub fn nearest_after(&self, target: usize, k: usize) -> Vec<(i64, usize)> {
+        let mut found = BinaryHeap::with_capacity(k + 1);
+        if k > 0 {
+            self.search(target, k, 0, self.order.len(), 0, &mut found);
+        }
+        found.into_sorted_vec()
+    }
+
+    fn search(&self, target: usize, k: usize, lo: usize, hi: usize, depth: usize, found: &mut BinaryHeap<(i64, usize)>) {
+        if lo >= hi {
+            return;
+        }
+        let mid = lo + (hi - lo) / 2;
+        let node = self.order[mid];
+        let (point, other) = (&self.boxes[target], &self.boxes[node]);
+
+        if node > target {
+            found.push((self.metric.distance(point, other), node));
+            if found.len() > k {
+                found.pop();
+            }
+        }
+
+        let axis = depth % self.boxes.dimensions;
+        let diff = point[axis] - other[axis];
+        let (near, far) = if diff < 0 { ((lo, mid), (mid + 1, hi)) } else { ((mid + 1, hi), (lo, mid)) };
+        self.search(target, k, near.0, near.1, depth + 1, found);
+        // the other side can only help if it's no further than the worst we have
+        if found.len() < k || found.peek().is_some_and(|&(worst, _)| self.metric.axis_distance(diff) <= worst) {
+            self.search(target, k, far.0, far.1, depth + 1, found);
+        }
+    }
+}
+
+fn build(boxes: &Boxes, order: &mut [usize], depth: usize) {
+    if order.len() <= 1 {
+        return;
+    }
+    let mid = order.len() / 2;
+    let axis = depth % boxes.dimensions;
+    order.select_nth_unstable_by_key(mid, |&i| boxes[i][axis]);
+    let (left, right) = order.split_at_mut(mid);
+    build(boxes, left, depth + 1);
+    build(boxes, &mut right[1..], depth + 1);
+}
+
+/// Every pair of boxes, closest first, found as they're asked for. Each box
+/// only remembers its nearest few boxes, and asks the tree for twice as
+/// many when it runs out.
+#[derive(Debug, Clone)]
+pub struct PairsByDistance {
+    tree: KdTree,
+    neighbours: Vec<Neighbours>,
+    next: BinaryHeap<Reverse<(i64, usize, usize)>>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Neighbours {
+    found: Vec<(i64, usize)>,
+    asked: usize,
+    taken: usize,
+}
+
+impl PairsByDistance {
+    pub fn new(boxes: &Boxes, metric: Metric) -> Self {
+        let mut pairs = Self {
+            tree: KdTree::new(boxes, metric),
+            neighbours: vec![Neighbours::default(); boxes.len()],
+            next: BinaryHeap::new(),
+        };
+        for i in 0..boxes.len() {
+            pairs.queue(i);
+        }
+        pairs
+    }
+
+    // put the next closest box after `i` up for selection
+    fn queue(&mut self, i: usize) {
+        let neighbours = &mut self.neighbours[i];
+        if neighbours.taken == neighbours.found.len() {
+            // the last query came back short, there's nothing left
+            if neighbours.found.len() < neighbours.asked {
+                return;
+            }
+            neighbours.asked = (neighbours.asked * 2).max(8);
+            neighbours.found = self.tree.nearest_after(i, neighbours.asked);
+        }
+        if let Some(&(distance, j)) = neighbours.found.get(neighbours.taken) {
+            self.next.push(Reverse((distance, i, j)));
+        }
+    }
+}
+
+impl Iterator for PairsByDistance {
+    type Item = Pair;
+
+    fn next(&mut self) -> Option<Pair> {
+        let Reverse((distance, a, b)) = self.next.pop()?;
+        self.neighbours[a].taken += 1;
+        self.queue(a);
+        Some(Pair { a, b, distance })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+    use crate::circuits::all_pairs;
+
+    fn boxes(n: usize, dimensions: usize, seed: u64) -> Boxes {
+        // small coordinates so there are plenty of ties
+        let mut state = seed;
+        let mut next = move || {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            ((state >> 33) % 20) as i64
+        };
+        Boxes::new(dimensions, (0..n).map(|_| (0..dimensions).map(|_| next()).collect())).unwrap()
+    }
+
+    #[rstest]
+    fn test_same_order_as_brute_force(
+        #[values(2, 3, 4)] dimensions: usize,
+        #[values(Metric::Euclidean, Metric::Manhattan, Metric::Chebyshev)] metric: Metric,
+    ) {
+        for seed in 0..3 {
+            let boxes = boxes(150, dimensions, seed);
+            let mut expected = all_pairs(&boxes, metric);
+            expected.sort_unstable_by_key(|p| (p.distance, p.a, p.b));
+
+            let lazy: Vec<Pair> = PairsByDistance::new(&boxes, metric).collect();
+            assert_eq!(expected, lazy);
+        }
+    }
+}
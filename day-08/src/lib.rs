@@ -1,2 +1,7 @@
+pub mod circuits;
+pub mod dsu;
+pub mod export;
+pub mod kdtree;
 pub mod part1;
 pub mod part2;
+pub mod timeline;
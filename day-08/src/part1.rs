@@ -1,12 +1,9 @@
-use std::collections::HashSet;
-
-use glam::IVec3;
-use itertools::Itertools;
 use miette::miette;
-use nom::{
-    character::complete::{char, line_ending, i32},
-    multi::separated_list1,
-    IResult, Parser,
+
+use crate::{
+    circuits::{Metric, parse_boxes},
+    dsu::Dsu,
+    kdtree::PairsByDistance,
 };
 
 #[tracing::instrument]
@@ -14,87 +11,24 @@ pub fn process(input: &str) -> miette::Result<String> {
     process_with_max(input, 1000)
 }
 
-pub fn process_with_max(input: &str, max: i32) -> miette::Result<String> {
-    let (_, lights) = read_input(input).map_err(|e| miette!("parse failed {}", e))?;
-
-    let pairs: Vec<Pair> = lights
-        .iter()
-        .tuple_combinations()
-        .map(|(a, b)| Pair::new(*a, *b))
-        .sorted_by_key(|d| d.distance)
-        .collect();
-
-    let strings: Vec<_> = connect_pairs(&pairs, max)
-        .into_iter()
-        .sorted_by_key(|s| std::cmp::Reverse(s.len()))
-        .collect();
-
-    Ok((strings[0].len()*strings[1].len()*strings[2].len()).to_string())
+pub fn process_with_max(input: &str, max: usize) -> miette::Result<String> {
+    process_with_metric(input, max, Metric::Euclidean)
 }
 
-fn connect_pairs(pairs: &[Pair], max: i32) -> Vec<HashSet<IVec3>> {
-    let mut groups: Vec<HashSet<IVec3>> = Vec::new();
-
-    for pair in pairs.iter().take(max as usize) {
-
-        let a_idx = groups.iter().position(|g| g.contains(&pair.a));
-        let b_idx = groups.iter().position(|g| g.contains(&pair.b));
-
-        match (a_idx, b_idx) {
-
-            (Some(i), Some(j)) if i == j => {}
-
-            (Some(i), Some(j)) => {
-                let b_group = groups.remove(j.max(i));
-                let a_group = groups.remove(i.min(j));
-                let mut merged = a_group;
-                merged.extend(b_group);
-                groups.push(merged);
-            }
-
-            (Some(i), None) => {
-                groups[i].insert(pair.b);
-            }
+pub fn process_with_metric(input: &str, max: usize, metric: Metric) -> miette::Result<String> {
+    let boxes = parse_boxes(input)?;
 
-            (None, Some(j)) => {
-                groups[j].insert(pair.a);
-            }
-
-            (None, None) => {
-                groups.push(HashSet::from([pair.a, pair.b]));
-            }
-        }
+    let mut circuits = Dsu::new(boxes.len());
+    for pair in PairsByDistance::new(&boxes, metric).take(max) {
+        circuits.union(pair.a, pair.b);
     }
 
-    groups
-}
-
-fn read_input(input: &str) -> IResult<&str, Vec<IVec3>> {
-    let (input, lights) = separated_list1(line_ending, light).parse(input)?;
-    Ok((input, lights))
-}
-
-fn light(input: &str) -> IResult<&str, IVec3> {
-    let (input, (x, _, y, _, z)) = (i32, char(','), i32, char(','), i32).parse(input)?;
-    Ok((input, IVec3::new(x, y, z)))
-}
-
-#[derive(Debug)]
-struct Pair {
-    a: IVec3,
-    b: IVec3,
-    distance: i64,
-}
-
-impl Pair {
-    fn new(a: IVec3, b: IVec3) -> Self {
-        let d = a - b;
-        let distance = (d.x as i64).pow(2) + (d.y as i64).pow(2) + (d.z as i64).pow(2);
-        Self { distance, a, b }
+    match circuits.sizes()[..] {
+        [a, b, c, ..] => Ok((a * b * c).to_string()),
+        _ => Err(miette!("need at least 3 circuits, found {}", circuits.components())),
     }
 }
 
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,90 +1,29 @@
-use std::collections::HashSet;
-
-use glam::I64Vec3;
-use itertools::Itertools;
 use miette::miette;
-use nom::{
-    character::complete::{char, line_ending, i64},
-    multi::separated_list1,
-    IResult, Parser,
+
+use crate::{
+    circuits::{Metric, parse_boxes},
+    dsu::Dsu,
+    kdtree::PairsByDistance,
 };
 
 #[tracing::instrument]
 pub fn process(input: &str) -> miette::Result<String> {
-    let (_, lights) = read_input(input).map_err(|e| miette!("parse failed {}", e))?;
-
-    let pairs: Vec<Pair> = lights
-        .iter()
-        .tuple_combinations()
-        .map(|(a, b)| Pair::new(*a, *b))
-        .sorted_by_key(|d| d.distance)
-        .collect();
-
-    let distance: i64 = connect_pairs(lights.len(),&pairs);
-
-    Ok(distance.to_string())
+    process_with_metric(input, Metric::Euclidean)
 }
 
-fn connect_pairs(num_lights: usize, pairs: &[Pair]) -> i64 {
-    let mut groups: Vec<HashSet<I64Vec3>> = Vec::new();
-
-    for pair in pairs {
-        let a_idx = groups.iter().position(|g| g.contains(&pair.a));
-        let b_idx = groups.iter().position(|g| g.contains(&pair.b));
-
-        match (a_idx, b_idx) {
+pub fn process_with_metric(input: &str, metric: Metric) -> miette::Result<String> {
+    let boxes = parse_boxes(input)?;
 
-            (Some(i), Some(j)) if i == j => {}
-
-            (Some(i), Some(j)) => {
-                let other = groups.remove(j.max(i));
-                groups[i.min(j)].extend(other);
-            }
-
-            (Some(i), None) => {
-                groups[i].insert(pair.b);
-            }
-
-            (None, Some(j)) => {
-                groups[j].insert(pair.a);
-            }
-
-            (None, None) => {
-                groups.push(HashSet::from([pair.a, pair.b]));
-            }
+    // kruskal, stopping at the connection that makes it all one circuit
+    let mut circuits = Dsu::new(boxes.len());
+    for pair in PairsByDistance::new(&boxes, metric) {
+        if circuits.union(pair.a, pair.b) && circuits.components() == 1 {
+            return Ok((boxes[pair.a][0] * boxes[pair.b][0]).to_string());
         }
-
-        if groups.len() == 1 && groups[0].len() == num_lights {
-            return pair.a.x * pair.b.x;
-        }
-    }
-    panic!("should not happen");
-}
-
-fn read_input(input: &str) -> IResult<&str, Vec<I64Vec3>> {
-    let (input, lights) = separated_list1(line_ending, light).parse(input)?;
-    Ok((input, lights))
-}
-
-fn light(input: &str) -> IResult<&str, I64Vec3> {
-    let (input, (x, _, y, _, z)) = (i64, char(','), i64, char(','), i64).parse(input)?;
-    Ok((input, I64Vec3::new(x, y, z)))
-}
-
-#[derive(Debug)]
-struct Pair {
-    a: I64Vec3,
-    b: I64Vec3,
-    distance: i64,
-}
-
-impl Pair {
-    fn new(a: I64Vec3, b: I64Vec3) -> Self {
-        Self { distance: a.distance_squared(b), a, b }
     }
+    Err(miette!("{} junction boxes never become one circuit", boxes.len()))
 }
 
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -115,4 +54,16 @@ mod tests {
         assert_eq!("25272", process(input)?);
         Ok(())
     }
+
+    #[test]
+    fn test_flat_manhattan() -> miette::Result<()> {
+        // the diagonal is shortest as the crow flies, but not along the streets
+        let input = "1,0
+5,4
+8,0
+";
+        assert_eq!("5", process_with_metric(input, Metric::Euclidean)?);
+        assert_eq!("40", process_with_metric(input, Metric::Manhattan)?);
+        Ok(())
+    }
 }
@@ -0,0 +1,164 @@
+use std::{collections::BTreeMap, fmt::Write};
+
+use crate::{
+    circuits::{Boxes, Metric},
+    dsu::Dsu,
+    kdtree::PairsByDistance,
+};
+
+/// The circuits after every connection that joined two of them, from the
+/// first pair up to the one that makes everything one circuit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timeline {
+    pub boxes: usize,
+    pub steps: Vec<Step>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    /// How many of the closest pairs have been looked at, this one included.
+    pub pairs: usize,
+    pub a: usize,
+    pub b: usize,
+    pub distance: i64,
+    pub circuits: usize,
+    /// Product of the sizes of the three largest circuits, `None` when
+    /// there are fewer than three.
+    pub top3: Option<usize>,
+}
+
+/// The circuits after some number of pairs, see [`Timeline::after`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct State {
+    pub circuits: usize,
+    pub top3: Option<usize>,
+}
+
+impl Timeline {
+    pub fn new(boxes: &Boxes, metric: Metric) -> Self {
+        let mut circuits = Dsu::new(boxes.len());
+        // how many circuits there are of every size
+        let mut sizes: BTreeMap<usize, usize> = BTreeMap::from([(1, boxes.len())]);
+        let mut steps = Vec::new();
+
+        for (i, pair) in PairsByDistance::new(boxes, metric).enumerate() {
+            if circuits.components() == 1 {
+                break;
+            }
+            let (size_a, size_b) = (circuits.size(pair.a), circuits.size(pair.b));
+            if !circuits.union(pair.a, pair.b) {
+                continue;
+            }
+            for size in [size_a, size_b] {
+                if let Some(count) = sizes.get_mut(&size) {
+                    *count -= 1;
+                    if *count == 0 {
+                        sizes.remove(&size);
+                    }
+                }
+            }
+            *sizes.entry(size_a + size_b).or_default() += 1;
+
+            steps.push(Step {
+                pairs: i + 1,
+                a: pair.a,
+                b: pair.b,
+                distance: pair.distance,
+                circuits: circuits.components(),
+                top3: top3(&sizes),
+            });
+        }
+
+        Self { boxes: boxes.len(), steps }
+    }
+
+    /// The circuits once the `k` closest pairs have been connected.
+    pub fn after(&self, k: usize) -> State {
+        let done = self.steps.partition_point(|s| s.pairs <= k);
+        match done.checked_sub(1) {
+            Some(i) => State { circuits: self.steps[i].circuits, top3: self.steps[i].top3 },
+            None => State { circuits: self.boxes, top3: (self.boxes >= 3).then_some(1) },
+        }
+    }
+
+    /// The connection that made everything one circuit.
+    pub fn all_connected(&self) -> Option<&Step> {
+        self.steps.last().filter(|s| s.circuits == 1)
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("pairs,a,b,distance,circuits,top3,all_connected\n");
+        for step in &self.steps {
+            let top3 = step.top3.map(|p| p.to_string()).unwrap_or_default();
+            writeln!(
+                csv,
+                "{},{},{},{},{},{},{}",
+                step.pairs,
+                step.a,
+                step.b,
+                step.distance,
+                step.circuits,
+                top3,
+                step.circuits == 1
+            )
+            .unwrap();
+        }
+        csv
+    }
+}
+
+fn top3(sizes: &BTreeMap<usize, usize>) -> Option<usize> {
+    let largest: Vec<usize> = sizes
+        .iter()
+        .rev()
+        .flat_map(|(&size, &count)| std::iter::repeat_n(size, count.min(3)))
+        .take(3)
+        .collect();
+    (largest.len() == 3).then(|| largest.iter().product())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuits::parse_boxes;
+
+    #[test]
+    fn test_timeline() -> miette::Result<()> {
+        let input = "162,817,812
+57,618,57
+906,360,560
+592,479,940
+352,342,300
+466,668,158
+542,29,236
+431,825,988
+739,650,466
+52,470,668
+216,146,977
+819,987,18
+117,168,530
+805,96,715
+346,949,466
+970,615,88
+941,993,340
+862,61,35
+984,92,344
+425,690,689
+";
+        let boxes = parse_boxes(input)?;
+        let timeline = Timeline::new(&boxes, Metric::Euclidean);
+
+        assert_eq!(State { circuits: 20, top3: Some(1) }, timeline.after(0));
+        assert_eq!(State { circuits: 11, top3: Some(40) }, timeline.after(10));
+        assert_eq!(State { circuits: 1, top3: None }, timeline.after(usize::MAX));
+
+        let last = timeline.all_connected().unwrap();
+        assert_eq!(25272, boxes[last.a][0] * boxes[last.b][0]);
+        assert_eq!(19, timeline.steps.len());
+
+        let csv = timeline.to_csv();
+        assert_eq!(20, csv.lines().count());
+        assert!(csv.lines().last().unwrap().ends_with(",1,,true"), "{csv}");
+        Ok(())
+    }
+}
@@ -1,11 +1,24 @@
-use day_09::part2::process;
-use miette::Context;
+use day_09::{part2::process, svg::render};
+use miette::{Context, IntoDiagnostic};
 
 #[tracing::instrument]
 fn main() -> miette::Result<()> {
     tracing_subscriber::fmt::init();
 
     let file = include_str!("../../input2.txt");
+
+    // `--svg <file>` draws the tiles with both answers, `--scale <units>`
+    // sets the size of a tile, by default it's fitted to 1000 units
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(i) = args.iter().position(|a| a == "--svg") {
+        let path = args.get(i + 1).ok_or_else(|| miette::miette!("--svg needs a file"))?;
+        let scale = match args.iter().position(|a| a == "--scale") {
+            Some(j) => Some(args.get(j + 1).ok_or_else(|| miette::miette!("--scale needs a number"))?.parse().into_diagnostic()?),
+            None => None,
+        };
+        std::fs::write(path, render(file, scale).context("render part 2")?).into_diagnostic()?;
+    }
+
     let result = process(file).context("process part 2")?;
     println!("{}", result);
     Ok(())
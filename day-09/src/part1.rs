@@ -2,37 +2,33 @@ use glam::I64Vec2;
 use itertools::Itertools;
 use miette::miette;
 use nom::{
-    character::complete::{char, i64, line_ending},
+    character::complete::line_ending,
     multi::separated_list1,
     IResult, Parser,
 };
 
+use crate::polygon::{Rectangle, red_tile};
+
 #[tracing::instrument]
 pub fn process(input: &str) -> miette::Result<String> {
     let (_, points) = read_input(input).map_err(|e| miette!("parse failed {}", e))?;
+    let largest = largest(&points).ok_or_else(|| miette!("need at least 2 red tiles"))?;
+    Ok(largest.area().to_string())
+}
 
-    let largest_area = points
+/// The largest rectangle between any two red tiles.
+pub fn largest(points: &[I64Vec2]) -> Option<Rectangle> {
+    points
         .iter()
         .tuple_combinations()
-        .map(|(a, b)| {
-            let d = a - b;
-            (d.x.abs() + 1) * (d.y.abs() + 1)
-        })
-        .max()
-        .unwrap();
-
-    Ok(largest_area.to_string())
+        .map(|(&a, &b)| Rectangle { a, b })
+        .max_by_key(Rectangle::area)
 }
 
 fn read_input(input: &str) -> IResult<&str, Vec<I64Vec2>> {
     separated_list1(line_ending, red_tile).parse(input)
 }
 
-fn red_tile(input: &str) -> IResult<&str, I64Vec2> {
-    let (input, (x, _, y)) = (i64, char(','), i64).parse(input)?;
-    Ok((input, I64Vec2::new(x, y)))
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,168 @@
+use glam::I64Vec2;
+use miette::miette;
+use nom::{
+    IResult, Parser,
+    character::complete::{char, i64},
+};
+
+/// The loop of red tiles in input order, the last one joins back up with
+/// the first. `lines` holds the input line of every vertex for errors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polygon {
+    pub vertices: Vec<I64Vec2>,
+    lines: Vec<usize>,
+}
+
+/// A horizontal or vertical side of the polygon, from one vertex to the next.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Edge {
+    pub start: I64Vec2,
+    pub end: I64Vec2,
+}
+
+impl Edge {
+    pub fn min(&self) -> I64Vec2 {
+        self.start.min(self.end)
+    }
+
+    pub fn max(&self) -> I64Vec2 {
+        self.start.max(self.end)
+    }
+
+    pub fn is_vertical(&self) -> bool {
+        self.start.x == self.end.x
+    }
+
+    // both are straight lines, so they meet exactly when their bounding boxes do
+    fn overlap(&self, other: &Edge) -> Option<(I64Vec2, I64Vec2)> {
+        let (min, max) = (self.min().max(other.min()), self.max().min(other.max()));
+        (min.x <= max.x && min.y <= max.y).then_some((min, max))
+    }
+}
+
+/// The rectangle with red tiles `a` and `b` in opposite corners.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rectangle {
+    pub a: I64Vec2,
+    pub b: I64Vec2,
+}
+
+impl Rectangle {
+    pub fn min(&self) -> I64Vec2 {
+        self.a.min(self.b)
+    }
+
+    pub fn max(&self) -> I64Vec2 {
+        self.a.max(self.b)
+    }
+
+    /// Number of tiles covered, corners included.
+    pub fn area(&self) -> i64 {
+        let d = (self.a - self.b).abs() + I64Vec2::ONE;
+        d.x * d.y
+    }
+}
+
+impl Polygon {
+    /// Read the vertices and check they make a closed, rectilinear loop
+    /// that doesn't cross itself.
+    pub fn parse(input: &str) -> miette::Result<Self> {
+        let mut vertices = Vec::new();
+        let mut lines = Vec::new();
+        for (i, line) in input.lines().enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+            let (_, tile) = red_tile(line.trim()).map_err(|e| miette!("line {}: parse failed {}", i + 1, e))?;
+            vertices.push(tile);
+            lines.push(i + 1);
+        }
+
+        let polygon = Self { vertices, lines };
+        polygon.validate()?;
+        Ok(polygon)
+    }
+
+    pub fn edges(&self) -> impl Iterator<Item = Edge> + '_ {
+        let n = self.vertices.len();
+        (0..n).map(move |i| Edge { start: self.vertices[i], end: self.vertices[(i + 1) % n] })
+    }
+
+    fn validate(&self) -> miette::Result<()> {
+        let n = self.vertices.len();
+        if n < 4 {
+            return Err(miette!("{} red tiles can't close a loop, it takes at least 4", n));
+        }
+
+        let edges: Vec<Edge> = self.edges().collect();
+        for (i, edge) in edges.iter().enumerate() {
+            let next = self.lines[(i + 1) % n];
+            if edge.start == edge.end {
+                return Err(miette!("line {}: same tile as line {}", next, self.lines[i]));
+            }
+            if edge.start.x != edge.end.x && edge.start.y != edge.end.y {
+                return Err(miette!(
+                    "line {}: {},{} is not in line with {},{} on line {}",
+                    next,
+                    edge.end.x,
+                    edge.end.y,
+                    edge.start.x,
+                    edge.start.y,
+                    self.lines[i]
+                ));
+            }
+        }
+
+        for i in 0..n {
+            for j in i + 1..n {
+                let Some((min, max)) = edges[i].overlap(&edges[j]) else { continue };
+                // neighbouring edges share a corner, but must not fold back over each other
+                let wraps = i == 0 && j == n - 1;
+                if (j == i + 1 || wraps) && min == max {
+                    continue;
+                }
+                // blame the later of the two edges
+                let (at, other) = if wraps { (i, j) } else { (j, i) };
+                return Err(miette!(
+                    "line {}: the edge from here crosses the edge from line {} at {},{}",
+                    self.lines[at],
+                    self.lines[other],
+                    min.x,
+                    min.y
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn red_tile(input: &str) -> IResult<&str, I64Vec2> {
+    let (input, (x, _, y)) = (i64, char(','), i64).parse(input)?;
+    Ok((input, I64Vec2::new(x, y)))
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[test]
+    fn test_parse() -> miette::Result<()> {
+        let polygon = Polygon::parse("7,1\n11,1\n11,7\n9,7\n9,5\n2,5\n2,3\n7,3\n")?;
+        assert_eq!(8, polygon.edges().count());
+        assert_eq!(Edge { start: I64Vec2::new(7, 3), end: I64Vec2::new(7, 1) }, polygon.edges().last().unwrap());
+        Ok(())
+    }
+
+    #[rstest]
+    #[case("1,1\n5,1\n5,5\n", "3 red tiles can't close a loop")]
+    #[case("1,1\n5,1\n5,5\n2,4\n1,5\n", "line 4: 2,4 is not in line with 5,5 on line 3")]
+    #[case("1,1\n5,1\n5,5\n2,5\n", "line 1: 1,1 is not in line with 2,5 on line 4")]
+    #[case("1,1\n5,1\n\n5,5\n5,5\n1,5\n", "line 5: same tile as line 4")]
+    // a figure of eight
+    #[case("0,0\n4,0\n4,4\n2,4\n2,-2\n0,-2\n", "line 4: the edge from here crosses the edge from line 1 at 2,0")]
+    // going back along the way it came
+    #[case("0,0\n4,0\n2,0\n2,3\n0,3\n", "line 2: the edge from here crosses the edge from line 1 at 2,0")]
+    fn test_invalid(#[case] input: &str, #[case] message: &str) {
+        let error = Polygon::parse(input).unwrap_err().to_string();
+        assert!(error.starts_with(message), "{error}");
+    }
+}
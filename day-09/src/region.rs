@@ -0,0 +1,156 @@
+use glam::I64Vec2;
+
+use crate::polygon::Polygon;
+
+/// The tiles inside the polygon, edges included, on a compressed grid. Every
+/// vertex coordinate gets its own row or column and the gaps between them
+/// become one row or column each, so all tiles in a cell are either in or
+/// out. A prefix sum over the cells that are out answers whether a
+/// rectangle is all in at once.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    xs: Vec<i64>,
+    ys: Vec<i64>,
+    width: usize,
+    // outside cells above and left of every cell, one row and column bigger
+    outside: Vec<u32>,
+}
+
+impl Region {
+    pub fn new(polygon: &Polygon) -> Self {
+        let xs = coordinates(polygon.vertices.iter().map(|v| v.x));
+        let ys = coordinates(polygon.vertices.iter().map(|v| v.y));
+        let (width, height) = (xs.len() * 2 - 1, ys.len() * 2 - 1);
+
+        let mut inside = vec![false; width * height];
+        for edge in polygon.edges() {
+            let (lo, hi) = (cell(&xs, &ys, edge.min()), cell(&xs, &ys, edge.max()));
+            for y in lo.1..=hi.1 {
+                for x in lo.0..=hi.0 {
+                    inside[y * width + x] = true;
+                }
+            }
+        }
+
+        // everything else is in when a ray going left crosses the edges an
+        // odd number of times, counting an edge from its top end up to but
+        // not including its bottom end
+        let verticals: Vec<_> = polygon.edges().filter(|e| e.is_vertical()).collect();
+        for y in 0..height {
+            let Some(tile_y) = sample(&ys, y) else { continue };
+            let mut crossings = vec![false; width];
+            for edge in &verticals {
+                if edge.min().y <= tile_y && tile_y < edge.max().y {
+                    crossings[cell(&xs, &ys, edge.start).0] ^= true;
+                }
+            }
+            let mut odd = false;
+            for x in 0..width {
+                inside[y * width + x] |= odd;
+                odd ^= crossings[x];
+            }
+        }
+
+        // cells without any tiles can't be out
+        let mut outside = vec![0u32; (width + 1) * (height + 1)];
+        for y in 0..height {
+            for x in 0..width {
+                let out = !inside[y * width + x] && sample(&xs, x).is_some() && sample(&ys, y).is_some();
+                outside[(y + 1) * (width + 1) + x + 1] =
+                    out as u32 + outside[y * (width + 1) + x + 1] + outside[(y + 1) * (width + 1) + x] - outside[y * (width + 1) + x];
+            }
+        }
+
+        Self { xs, ys, width, outside }
+    }
+
+    /// Whether every tile of the rectangle with corners `a` and `b` is red
+    /// or green.
+    pub fn contains_rect(&self, a: I64Vec2, b: I64Vec2) -> bool {
+        let (Some(lo), Some(hi)) = (self.cell(a.min(b)), self.cell(a.max(b))) else { return false };
+        let w = self.width + 1;
+        let count = self.outside[(hi.1 + 1) * w + hi.0 + 1] + self.outside[lo.1 * w + lo.0]
+            - self.outside[lo.1 * w + hi.0 + 1]
+            - self.outside[(hi.1 + 1) * w + lo.0];
+        count == 0
+    }
+
+    pub fn contains(&self, tile: I64Vec2) -> bool {
+        self.contains_rect(tile, tile)
+    }
+
+    fn cell(&self, tile: I64Vec2) -> Option<(usize, usize)> {
+        Some((index(&self.xs, tile.x)?, index(&self.ys, tile.y)?))
+    }
+}
+
+fn coordinates(values: impl Iterator<Item = i64>) -> Vec<i64> {
+    let mut values: Vec<i64> = values.collect();
+    values.sort_unstable();
+    values.dedup();
+    values
+}
+
+// the vertex coordinates are on even cells, the gaps after them on odd ones
+fn index(values: &[i64], v: i64) -> Option<usize> {
+    match values.binary_search(&v) {
+        Ok(i) => Some(i * 2),
+        Err(i) if i > 0 && i < values.len() => Some(i * 2 - 1),
+        Err(_) => None,
+    }
+}
+
+// a tile in the cell, gaps between neighbouring coordinates have none
+fn sample(values: &[i64], i: usize) -> Option<i64> {
+    let v = values[i / 2] + (i % 2) as i64;
+    (i.is_multiple_of(2) || v < values[i / 2 + 1]).then_some(v)
+}
+
+fn cell(xs: &[i64], ys: &[i64], vertex: I64Vec2) -> (usize, usize) {
+    (xs.binary_search(&vertex.x).unwrap_or_default() * 2, ys.binary_search(&vertex.y).unwrap_or_default() * 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use rstest::rstest;
+
+    use super::*;
+
+    // the slow way, one tile at a time
+    fn tile_inside(polygon: &Polygon, tile: I64Vec2) -> bool {
+        let mut odd = false;
+        for edge in polygon.edges() {
+            let (lo, hi) = (edge.min(), edge.max());
+            if lo.x <= tile.x && tile.x <= hi.x && lo.y <= tile.y && tile.y <= hi.y {
+                return true;
+            }
+            if edge.is_vertical() && lo.x < tile.x && lo.y <= tile.y && tile.y < hi.y {
+                odd = !odd;
+            }
+        }
+        odd
+    }
+
+    #[rstest]
+    #[case("7,1\n11,1\n11,7\n9,7\n9,5\n2,5\n2,3\n7,3\n")]
+    #[case("0,0\n10,0\n10,10\n7,10\n7,3\n3,3\n3,10\n0,10\n")]
+    #[case("0,0\n4,0\n4,1\n5,1\n5,0\n9,0\n9,6\n6,6\n6,2\n3,2\n3,6\n0,6\n")]
+    fn test_against_tiles(#[case] input: &str) -> miette::Result<()> {
+        let polygon = Polygon::parse(input)?;
+        let region = Region::new(&polygon);
+
+        for (a, b) in polygon.vertices.iter().tuple_combinations() {
+            let (lo, hi) = (a.min(*b), a.max(*b));
+            let all_in = (lo.y..=hi.y).all(|y| (lo.x..=hi.x).all(|x| tile_inside(&polygon, I64Vec2::new(x, y))));
+            assert_eq!(all_in, region.contains_rect(*a, *b), "{a} {b}");
+        }
+        for y in -1..12 {
+            for x in -1..12 {
+                let tile = I64Vec2::new(x, y);
+                assert_eq!(tile_inside(&polygon, tile), region.contains(tile), "{tile}");
+            }
+        }
+        Ok(())
+    }
+}
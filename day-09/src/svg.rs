@@ -0,0 +1,141 @@
+use std::fmt::Write;
+
+use miette::miette;
+
+use crate::{
+    part1::largest,
+    part2::largest_inside,
+    polygon::{Polygon, Rectangle},
+};
+
+/// Draws the tiles as an SVG, every tile is `scale` units wide so large
+/// inputs can be shrunk down.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Svg {
+    pub scale: f64,
+}
+
+impl Svg {
+    pub fn new(scale: f64) -> Self {
+        Self { scale }
+    }
+
+    /// A scale that makes the longest side of the polygon `size` units.
+    pub fn fit(polygon: &Polygon, size: f64) -> Self {
+        let (min, max) = bounds(polygon);
+        let longest = (max - min).max_element() + 1;
+        Self { scale: size / longest as f64 }
+    }
+
+    /// The polygon filled in green with its red tiles, and the best part 1
+    /// and part 2 rectangles drawn on top.
+    pub fn render(&self, polygon: &Polygon, part1: Option<Rectangle>, part2: Option<Rectangle>) -> String {
+        let (min, max) = bounds(polygon);
+        let s = self.scale;
+        // top left corner of a tile
+        let x = |v: i64| (v - min.x) as f64 * s;
+        let y = |v: i64| (v - min.y) as f64 * s;
+        // red tiles and outlines stay visible however far it's zoomed out
+        let marker = s.max(2.0);
+        let (width, height) = (x(max.x + 1), y(max.y + 1));
+
+        let mut svg = String::new();
+        writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {width} {height}" width="{width}" height="{height}">"#
+        )
+        .unwrap();
+        writeln!(svg, r##"  <rect width="100%" height="100%" fill="#101018"/>"##).unwrap();
+
+        // through the middle of the tiles, with a stroke one tile wide so the
+        // edges get covered as well
+        let points: Vec<String> = polygon
+            .vertices
+            .iter()
+            .map(|v| format!("{},{}", x(v.x) + s / 2.0, y(v.y) + s / 2.0))
+            .collect();
+        writeln!(
+            svg,
+            r##"  <polygon points="{}" fill="#2e8b57" stroke="#2e8b57" stroke-width="{}" stroke-linejoin="miter"/>"##,
+            points.join(" "),
+            s
+        )
+        .unwrap();
+
+        for v in &polygon.vertices {
+            writeln!(
+                svg,
+                r##"  <rect x="{}" y="{}" width="{marker}" height="{marker}" fill="#dc3c3c"/>"##,
+                x(v.x) + (s - marker) / 2.0,
+                y(v.y) + (s - marker) / 2.0
+            )
+            .unwrap();
+        }
+
+        for (rect, colour, label) in [(part1, "#4363d8", "part 1"), (part2, "#ffe119", "part 2")] {
+            let Some(rect) = rect else { continue };
+            let (lo, hi) = (rect.min(), rect.max());
+            writeln!(
+                svg,
+                r#"  <rect x="{}" y="{}" width="{}" height="{}" fill="{colour}" fill-opacity="0.25" stroke="{colour}" stroke-width="{marker}"><title>{label}: {}</title></rect>"#,
+                x(lo.x),
+                y(lo.y),
+                (hi.x - lo.x + 1) as f64 * s,
+                (hi.y - lo.y + 1) as f64 * s,
+                rect.area()
+            )
+            .unwrap();
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+fn bounds(polygon: &Polygon) -> (glam::I64Vec2, glam::I64Vec2) {
+    let min = polygon.vertices.iter().copied().reduce(|a, b| a.min(b)).unwrap_or_default();
+    let max = polygon.vertices.iter().copied().reduce(|a, b| a.max(b)).unwrap_or_default();
+    (min, max)
+}
+
+/// Parse the input and draw it with both answers, scaled by `scale` or to
+/// fit in 1000 units when there's none.
+pub fn render(input: &str, scale: Option<f64>) -> miette::Result<String> {
+    let polygon = Polygon::parse(input)?;
+    let svg = match scale {
+        Some(scale) if scale > 0.0 => Svg::new(scale),
+        Some(scale) => return Err(miette!("scale must be above 0, got {}", scale)),
+        None => Svg::fit(&polygon, 1000.0),
+    };
+    Ok(svg.render(&polygon, largest(&polygon.vertices), largest_inside(&polygon)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() -> miette::Result<()> {
+        let input = "7,1
+11,1
+11,7
+9,7
+9,5
+2,5
+2,3
+7,3
+";
+        let svg = render(input, Some(10.0))?;
+        assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 100 70""#), "{svg}");
+        assert!(svg.contains(r#"<polygon points="55,5 95,5 95,65"#), "{svg}");
+        assert_eq!(8, svg.matches("#dc3c3c").count());
+        assert!(svg.contains("<title>part 1: 50</title>"), "{svg}");
+        assert!(svg.contains("<title>part 2: 24</title>"), "{svg}");
+
+        // 100 tiles across squeezed into 1000 units
+        let svg = render("0,0\n99,0\n99,9\n0,9\n", None)?;
+        assert!(svg.contains(r#"viewBox="0 0 1000 100""#), "{svg}");
+        assert!(render(input, Some(0.0)).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,361 @@
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::Path,
+    thread,
+    time::Duration,
+};
+
+use miette::{IntoDiagnostic, miette};
+
+/// One snapshot of a character grid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub width: usize,
+    pub height: usize,
+    cells: Vec<char>,
+}
+
+impl Frame {
+    pub fn new(width: usize, height: usize, fill: char) -> Self {
+        Self { width, height, cells: vec![fill; width * height] }
+    }
+
+    /// Build a frame from lines of text, short lines are padded with `fill`.
+    pub fn from_text(text: &str, fill: char) -> Self {
+        let width = text.lines().map(|l| l.chars().count()).max().unwrap_or(0);
+        let height = text.lines().count();
+        let mut frame = Self::new(width, height, fill);
+        for (y, line) in text.lines().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                frame.set(x, y, c);
+            }
+        }
+        frame
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<char> {
+        (x < self.width && y < self.height).then(|| self.cells[y * self.width + x])
+    }
+
+    /// Cells outside the frame are ignored.
+    pub fn set(&mut self, x: usize, y: usize, c: char) {
+        if x < self.width && y < self.height {
+            self.cells[y * self.width + x] = c;
+        }
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[char]> {
+        self.cells.chunks(self.width.max(1))
+    }
+}
+
+impl std::fmt::Display for Frame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.rows() {
+            writeln!(f, "{}", row.iter().collect::<String>())?;
+        }
+        Ok(())
+    }
+}
+
+/// Colours used when a frame is turned into an image, characters without
+/// a colour are drawn in `default`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Palette {
+    pub colours: HashMap<char, [u8; 3]>,
+    pub default: [u8; 3],
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        let colours = HashMap::from([
+            ('.', [16, 16, 24]),
+            (' ', [16, 16, 24]),
+            ('@', [230, 230, 230]),
+            ('#', [120, 120, 120]),
+            ('x', [220, 60, 60]),
+            ('S', [250, 200, 40]),
+            ('^', [60, 140, 230]),
+            ('|', [80, 220, 120]),
+        ]);
+        Self { colours, default: [200, 0, 200] }
+    }
+}
+
+impl Palette {
+    pub fn with(mut self, c: char, rgb: [u8; 3]) -> Self {
+        self.colours.insert(c, rgb);
+        self
+    }
+
+    fn colour(&self, c: char) -> [u8; 3] {
+        self.colours.get(&c).copied().unwrap_or(self.default)
+    }
+}
+
+/// Collects frames of a grid as a solver runs, either as full snapshots or
+/// as a list of changed cells on top of the previous frame.
+#[derive(Debug, Clone, Default)]
+pub struct Recorder {
+    frames: Vec<Frame>,
+    pub palette: Palette,
+}
+
+impl Recorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, frame: Frame) {
+        self.frames.push(frame);
+    }
+
+    /// Apply the changed cells to the last frame and record the result.
+    pub fn push_diff(&mut self, changes: impl IntoIterator<Item = (usize, usize, char)>) -> miette::Result<()> {
+        let mut frame = self
+            .frames
+            .last()
+            .cloned()
+            .ok_or_else(|| miette!("a diff needs a frame to start from"))?;
+        for (x, y, c) in changes {
+            frame.set(x, y, c);
+        }
+        self.frames.push(frame);
+        Ok(())
+    }
+
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Replay the frames in a terminal, redrawing in place.
+    pub fn play(&self, out: &mut impl Write, fps: f64) -> miette::Result<()> {
+        let delay = Duration::from_secs_f64(1.0 / fps.max(0.1));
+        for (i, frame) in self.frames.iter().enumerate() {
+            writeln!(out, "\x1b[2J\x1b[H{}frame {}/{}", frame, i + 1, self.frames.len()).into_diagnostic()?;
+            out.flush().into_diagnostic()?;
+            thread::sleep(delay);
+        }
+        Ok(())
+    }
+
+    /// Encode all frames as one animated GIF, every cell becomes a
+    /// `scale` x `scale` block of pixels.
+    pub fn write_gif(&self, out: impl Write, scale: usize, delay_ms: u16) -> miette::Result<()> {
+        let first = self.frames.first().ok_or_else(|| miette!("nothing recorded"))?;
+        if let Some((i, frame)) = self
+            .frames
+            .iter()
+            .enumerate()
+            .find(|(_, f)| (f.width, f.height) != (first.width, first.height))
+        {
+            return Err(miette!(
+                "frame {} is {}x{}, but a gif needs every frame to be {}x{} like the first",
+                i,
+                frame.width,
+                frame.height,
+                first.width,
+                first.height
+            ));
+        }
+        let (width, height) = pixel_size::<u16>(first, scale, "gif")?;
+
+        // gif frames index into a palette, so collect every character we used
+        let mut glyphs: Vec<char> = self.frames.iter().flat_map(|f| f.cells.iter().copied()).collect();
+        glyphs.sort_unstable();
+        glyphs.dedup();
+        if glyphs.len() > 256 {
+            return Err(miette!("a gif can't hold {} different cells", glyphs.len()));
+        }
+        let index: HashMap<char, u8> = glyphs.iter().enumerate().map(|(i, &c)| (c, i as u8)).collect();
+        let palette: Vec<u8> = glyphs.iter().flat_map(|&c| self.palette.colour(c)).collect();
+
+        let mut encoder = gif::Encoder::new(out, width, height, &palette).into_diagnostic()?;
+        encoder.set_repeat(gif::Repeat::Infinite).into_diagnostic()?;
+
+        for frame in &self.frames {
+            let pixels = scaled(frame, scale, |c| [index[&c]]);
+            let mut image = gif::Frame::from_indexed_pixels(width, height, pixels, None);
+            image.delay = delay_ms / 10;
+            encoder.write_frame(&image).into_diagnostic()?;
+        }
+        Ok(())
+    }
+
+    pub fn save_gif(&self, path: impl AsRef<Path>, scale: usize, delay_ms: u16) -> miette::Result<()> {
+        let file = File::create(path).into_diagnostic()?;
+        self.write_gif(BufWriter::new(file), scale, delay_ms)
+    }
+
+    /// Write every frame to `dir` as `frame-0000.png`, `frame-0001.png`, ...
+    pub fn save_png_sequence(&self, dir: impl AsRef<Path>, scale: usize) -> miette::Result<()> {
+        let sizes = self
+            .frames
+            .iter()
+            .map(|frame| pixel_size::<u32>(frame, scale, "png"))
+            .collect::<miette::Result<Vec<_>>>()?;
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir).into_diagnostic()?;
+
+        for (i, (frame, (width, height))) in self.frames.iter().zip(sizes).enumerate() {
+            let file = File::create(dir.join(format!("frame-{i:04}.png"))).into_diagnostic()?;
+            let mut encoder = png::Encoder::new(BufWriter::new(file), width, height);
+            encoder.set_color(png::ColorType::Rgb);
+            encoder.set_depth(png::BitDepth::Eight);
+
+            let mut writer = encoder.write_header().into_diagnostic()?;
+            writer
+                .write_image_data(&scaled(frame, scale, |c| self.palette.colour(c)))
+                .into_diagnostic()?;
+        }
+        Ok(())
+    }
+}
+
+/// What the runner asked for on the command line: `--play [fps]`,
+/// `--gif <file>`, `--png <dir>` and `--scale <pixels per cell>`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Output {
+    pub play: Option<f64>,
+    pub gif: Option<String>,
+    pub png: Option<String>,
+    pub scale: usize,
+}
+
+impl Output {
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> miette::Result<Self> {
+        let mut output = Self { play: None, gif: None, png: None, scale: 4 };
+        let mut args = args.into_iter().peekable();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--play" => {
+                    let fps = args.next_if(|a| !a.starts_with("--"));
+                    output.play = Some(fps.map_or(Ok(10.0), |f| f.parse()).into_diagnostic()?);
+                }
+                "--gif" => output.gif = Some(args.next().ok_or_else(|| miette!("--gif needs a file"))?),
+                "--png" => output.png = Some(args.next().ok_or_else(|| miette!("--png needs a directory"))?),
+                "--scale" => {
+                    let scale = args.next().ok_or_else(|| miette!("--scale needs a number"))?;
+                    output.scale = scale.parse().into_diagnostic()?;
+                    if output.scale == 0 {
+                        return Err(miette!("--scale must be above 0"));
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(output)
+    }
+
+    pub fn is_requested(&self) -> bool {
+        self.play.is_some() || self.gif.is_some() || self.png.is_some()
+    }
+
+    pub fn write(&self, recorder: &Recorder) -> miette::Result<()> {
+        if let Some(fps) = self.play {
+            recorder.play(&mut std::io::stdout(), fps)?;
+        }
+        if let Some(path) = &self.gif {
+            recorder.save_gif(path, self.scale, 100)?;
+        }
+        if let Some(dir) = &self.png {
+            recorder.save_png_sequence(dir, self.scale)?;
+        }
+        Ok(())
+    }
+}
+
+// blow every cell up into a square of pixels
+// width and height of the frame in pixels, as whatever integer the image
+// format wants, instead of truncating when it doesn't fit
+fn pixel_size<N: TryFrom<usize>>(frame: &Frame, scale: usize, format: &str) -> miette::Result<(N, N)> {
+    if scale == 0 {
+        return Err(miette!("scale must be above 0"));
+    }
+    let too_large = || miette!("{}x{} cells at scale {} is too large for a {}", frame.width, frame.height, scale, format);
+    let width = frame.width.checked_mul(scale).and_then(|w| N::try_from(w).ok()).ok_or_else(too_large)?;
+    let height = frame.height.checked_mul(scale).and_then(|h| N::try_from(h).ok()).ok_or_else(too_large)?;
+    Ok((width, height))
+}
+
+fn scaled<const N: usize>(frame: &Frame, scale: usize, pixel: impl Fn(char) -> [u8; N]) -> Vec<u8> {
+    let mut pixels = Vec::with_capacity(frame.width * frame.height * scale * scale * N);
+    for row in frame.rows() {
+        let line: Vec<u8> = row
+            .iter()
+            .flat_map(|&c| std::iter::repeat_n(pixel(c), scale).flatten())
+            .collect();
+        for _ in 0..scale {
+            pixels.extend_from_slice(&line);
+        }
+    }
+    pixels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diffs() -> miette::Result<()> {
+        let mut recorder = Recorder::new();
+        assert!(recorder.push_diff([(0, 0, 'x')]).is_err());
+
+        recorder.push(Frame::from_text("@@\n@", '.'));
+        recorder.push_diff([(0, 0, 'x'), (5, 5, 'x')])?;
+        recorder.push_diff([(1, 1, '@')])?;
+
+        let frames: Vec<String> = recorder.frames().iter().map(|f| f.to_string()).collect();
+        assert_eq!(vec!["@@\n@.\n", "x@\n@.\n", "x@\n@@\n"], frames);
+        Ok(())
+    }
+
+    #[test]
+    fn test_output_args() -> miette::Result<()> {
+        let args = |a: &str| a.split_whitespace().map(String::from).collect::<Vec<_>>();
+        let output = Output::from_args(args("run --gif out.gif --scale 2"))?;
+        assert_eq!((Some("out.gif".to_string()), 2), (output.gif, output.scale));
+        assert!(Output::from_args(args("--gif out.gif --scale 0")).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_gif() -> miette::Result<()> {
+        let mut recorder = Recorder::new();
+        recorder.push(Frame::from_text("@.\n.@", '.'));
+        recorder.push_diff([(0, 0, 'x')])?;
+
+        let mut gif = Vec::new();
+        recorder.write_gif(&mut gif, 4, 100)?;
+        assert!(gif.starts_with(b"GIF89a"));
+
+        assert!(recorder.write_gif(Vec::new(), 40_000, 100).is_err());
+        assert!(recorder.write_gif(Vec::new(), 0, 100).is_err());
+        recorder.push(Frame::new(3, 2, '.'));
+        assert!(recorder.write_gif(Vec::new(), 4, 100).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_png_scale() {
+        let mut recorder = Recorder::new();
+        recorder.push(Frame::from_text("@.\n.@", '.'));
+
+        // both fail before anything is written
+        let dir = std::env::temp_dir().join("frame-recorder-test-png-scale");
+        assert!(recorder.save_png_sequence(&dir, 0).is_err());
+        assert!(recorder.save_png_sequence(&dir, usize::MAX).is_err());
+        assert!(!dir.exists());
+    }
+}
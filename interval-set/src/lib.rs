@@ -0,0 +1,225 @@
+use std::{fmt::Debug, ops::RangeInclusive};
+
+/// Unsigned integers that can be the ends of an interval.
+pub trait Endpoint: Copy + Ord + Debug {
+    const MIN: Self;
+    const MAX: Self;
+
+    fn next(self) -> Option<Self>;
+    fn prev(self) -> Option<Self>;
+    /// Number of values in `start..=end`, saturating at `u128::MAX`.
+    fn span(start: Self, end: Self) -> u128;
+}
+
+macro_rules! impl_endpoint {
+    ($($t:ty),*) => {
+        $(
+            impl Endpoint for $t {
+                const MIN: Self = <$t>::MIN;
+                const MAX: Self = <$t>::MAX;
+
+                fn next(self) -> Option<Self> {
+                    self.checked_add(1)
+                }
+
+                fn prev(self) -> Option<Self> {
+                    self.checked_sub(1)
+                }
+
+                fn span(start: Self, end: Self) -> u128 {
+                    ((end - start) as u128).saturating_add(1)
+                }
+            }
+        )*
+    };
+}
+
+impl_endpoint!(u8, u16, u32, u64, u128, usize);
+
+/// A set of integers kept as sorted, disjoint and non-adjacent inclusive
+/// ranges, so `3-5` and `6-8` are stored as `3-8`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct IntervalSet<T> {
+    ranges: Vec<RangeInclusive<T>>,
+}
+
+impl<T: Endpoint> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self { ranges: Vec::new() }
+    }
+
+    /// Add a range, empty ranges are ignored.
+    pub fn insert(&mut self, range: RangeInclusive<T>) {
+        if range.is_empty() {
+            return;
+        }
+        self.ranges.push(range);
+        self.normalize();
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.position(value).is_some()
+    }
+
+    /// Index of the merged range holding `value`.
+    pub fn position(&self, value: T) -> Option<usize> {
+        // the last range starting at or before `value` is the only candidate
+        let i = self.ranges.partition_point(|r| *r.start() <= value);
+        (i > 0 && value <= *self.ranges[i - 1].end()).then(|| i - 1)
+    }
+
+    /// Number of values covered, saturating at `u128::MAX`.
+    pub fn len(&self) -> u128 {
+        self.ranges
+            .iter()
+            .fold(0u128, |acc, r| acc.saturating_add(T::span(*r.start(), *r.end())))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn ranges(&self) -> &[RangeInclusive<T>] {
+        &self.ranges
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.ranges.iter().cloned()
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        self.iter().chain(other.iter()).collect()
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (&self.ranges[i], &other.ranges[j]);
+            let start = *a.start().max(b.start());
+            let end = *a.end().min(b.end());
+            if start <= end {
+                ranges.push(start..=end);
+            }
+            if a.end() < b.end() {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        Self { ranges }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        self.intersection(&other.complement(T::MIN..=T::MAX))
+    }
+
+    /// Everything inside `bounds` that is not in the set.
+    pub fn complement(&self, bounds: RangeInclusive<T>) -> Self {
+        let mut ranges = Vec::new();
+        if bounds.is_empty() {
+            return Self { ranges };
+        }
+
+        let mut next = Some(*bounds.start());
+        for r in &self.ranges {
+            let Some(start) = next else { break };
+            if let Some(gap_end) = r.start().prev()
+                && start <= gap_end
+            {
+                ranges.push(start..=gap_end.min(*bounds.end()));
+            }
+            next = r.end().next().map(|n| n.max(start));
+        }
+        if let Some(start) = next {
+            ranges.push(start..=*bounds.end());
+        }
+
+        ranges.retain(|r| !r.is_empty() && r.start() <= bounds.end());
+        Self { ranges }
+    }
+
+    fn normalize(&mut self) {
+        self.ranges.retain(|r| !r.is_empty());
+        self.ranges.sort_by_key(|r| *r.start());
+
+        let mut merged: Vec<RangeInclusive<T>> = Vec::with_capacity(self.ranges.len());
+        for range in self.ranges.drain(..) {
+            match merged.last_mut() {
+                // overlapping or touching, `end + 1` is unreachable at MAX
+                Some(last) if last.end().next().is_none_or(|n| *range.start() <= n) => {
+                    if range.end() > last.end() {
+                        *last = *last.start()..=*range.end();
+                    }
+                }
+                _ => merged.push(range),
+            }
+        }
+        self.ranges = merged;
+    }
+}
+
+impl<T: Endpoint> FromIterator<RangeInclusive<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<T>>>(iter: I) -> Self {
+        let mut set = Self { ranges: iter.into_iter().collect() };
+        set.normalize();
+        set
+    }
+}
+
+impl<T: Endpoint> Extend<RangeInclusive<T>> for IntervalSet<T> {
+    fn extend<I: IntoIterator<Item = RangeInclusive<T>>>(&mut self, iter: I) {
+        self.ranges.extend(iter);
+        self.normalize();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(ranges: &[(u64, u64)]) -> IntervalSet<u64> {
+        ranges.iter().map(|&(a, b)| a..=b).collect()
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(vec![3..=8, 10..=20], set(&[(16, 20), (10, 14), (6, 8), (3, 5), (12, 18)]).ranges());
+        assert_eq!(vec![0..=u64::MAX], set(&[(5, u64::MAX), (0, 4), (u64::MAX, u64::MAX)]).ranges());
+        assert!(set(&[(5, 4)]).is_empty());
+        assert_eq!(0, IntervalSet::<u64>::new().len());
+        assert_eq!(1 << 64, set(&[(0, u64::MAX)]).len());
+    }
+
+    #[test]
+    fn test_contains() {
+        let s = set(&[(3, 5), (10, 14), (16, 20)]);
+        let found: Vec<u64> = (0..25).filter(|&v| s.contains(v)).collect();
+        assert_eq!(vec![3, 4, 5, 10, 11, 12, 13, 14, 16, 17, 18, 19, 20], found);
+        assert_eq!(Some(1), s.position(12));
+        assert_eq!(None, s.position(15));
+    }
+
+    #[test]
+    fn test_set_algebra() {
+        let a = set(&[(1, 10), (20, 30)]);
+        let b = set(&[(5, 25), (40, 50)]);
+
+        assert_eq!(vec![1..=30, 40..=50], a.union(&b).ranges());
+        assert_eq!(vec![5..=10, 20..=25], a.intersection(&b).ranges());
+        assert_eq!(vec![1..=4, 26..=30], a.difference(&b).ranges());
+        assert_eq!(vec![0..=0, 11..=19, 31..=35], a.complement(0..=35).ranges());
+        assert_eq!(vec![11..=19], a.complement(5..=25).ranges());
+        assert_eq!(vec![0..=0, 11..=19, 31..=u64::MAX], a.complement(0..=u64::MAX).ranges());
+        assert!(set(&[(0, u64::MAX)]).complement(0..=u64::MAX).is_empty());
+
+        // every value agrees with the plain definition
+        for v in 0..60 {
+            assert_eq!(a.contains(v) || b.contains(v), a.union(&b).contains(v));
+            assert_eq!(a.contains(v) && b.contains(v), a.intersection(&b).contains(v));
+            assert_eq!(a.contains(v) && !b.contains(v), a.difference(&b).contains(v));
+        }
+    }
+}